@@ -9,6 +9,7 @@ use secret_structs::secret_block;
 
 use session_types::*;
 
+mod protocol;
 mod util;
 
 const GRID_SIZE: usize = 10;
@@ -303,8 +304,9 @@ fn game_loop_a(mut player: Player<lat::Label_A>, chan: session_types::Chan<(), P
         let c1 = c.send(guess);
         let c2 = match c1.offer() {
             Left(l) => {
-                let (c2, did_hit) = l.recv();
-                if did_hit {
+                // Player B's answer is public, like the guess itself.
+                let (c2, did_hit) = protocol::recv_secret::<_, _, _, lat::Label_Empty>(l);
+                if *did_hit.get_value_ref() {
                     player.guesses[guess.0][guess.1] = CellStatus::Hit;
                 } else {
                     player.guesses[guess.0][guess.1] = CellStatus::Empty;
@@ -321,13 +323,13 @@ fn game_loop_a(mut player: Player<lat::Label_A>, chan: session_types::Chan<(), P
 
         let (c3, guess) = c2.recv();
 
-        let is_hit: bool = secret_block!(lat::Label_A {
+        // Player B learns whether its guess hit.
+        let is_hit: st::Secret<bool, lat::Label_Empty> = secret_block!(lat::Label_A {
             wrap_secret(is_occupied(unwrap_secret_ref(&player.ship_positions), guess.0, guess.1))
         })
-        .declassify()
-        .get_value_consume();
+        .declassify();
 
-        if is_hit {
+        if *is_hit.get_value_ref() {
             println!("Hit!");
             player_b_guesses[guess.0][guess.1] = CellStatus::Hit;
         } else {
@@ -336,7 +338,7 @@ fn game_loop_a(mut player: Player<lat::Label_A>, chan: session_types::Chan<(), P
         }
 
         if !did_win(&player_b_guesses) {
-            c = c3.sel1().send(is_hit).zero();
+            c = protocol::send_secret(c3.sel1(), is_hit).zero();
         } else {
             println!("Player B wins.");
             c3.sel2().close();
@@ -352,13 +354,13 @@ fn game_loop_b(mut player: Player<lat::Label_B>, chan: session_types::Chan<(), P
     loop {
         let (c1, guess) = c.recv();
 
-        let is_hit: bool = secret_block!(lat::Label_B {
+        // Player A learns whether its guess hit.
+        let is_hit: st::Secret<bool, lat::Label_Empty> = secret_block!(lat::Label_B {
             wrap_secret(is_occupied(unwrap_secret_ref(&player.ship_positions), guess.0, guess.1))
         })
-        .declassify()
-        .get_value_consume();
+        .declassify();
 
-        if is_hit {
+        if *is_hit.get_value_ref() {
             println!("Hit!");
             player_a_guesses[guess.0][guess.1] = CellStatus::Hit;
         } else {
@@ -372,7 +374,7 @@ fn game_loop_b(mut player: Player<lat::Label_B>, chan: session_types::Chan<(), P
             return;
         }
 
-        let c1 = c1.sel1().send(is_hit);
+        let c1 = protocol::send_secret(c1.sel1(), is_hit);
 
         println!("Player B's guesses:");
         print_grid(&player.guesses);
@@ -385,8 +387,9 @@ fn game_loop_b(mut player: Player<lat::Label_B>, chan: session_types::Chan<(), P
         let c1 = c1.send(guess);
         match c1.offer() {
             Left(l) => {
-                let (c2, did_hit) = l.recv();
-                if did_hit {
+                // Player A's answer is public, like the guess itself.
+                let (c2, did_hit) = protocol::recv_secret::<_, _, _, lat::Label_Empty>(l);
+                if *did_hit.get_value_ref() {
                     player.guesses[guess.0][guess.1] = CellStatus::Hit;
                 } else {
                     player.guesses[guess.0][guess.1] = CellStatus::Empty;
@@ -0,0 +1,51 @@
+// Helpers for sending secrets over session_types channels. A channel carries plaintext, so only a
+// value the caller has already declassified (labeled Label_Empty) can be sent, which keeps each
+// declassification explicit at the send site. The receiver labels what it gets.
+
+use secret_structs::lattice as lat;
+use secret_structs::secret::{self as st, Classifiable, InvisibleSideEffectFree, SecretValueSafe};
+use session_types::{Chan, Recv};
+
+/// Sends a declassified value, e.g., `send_secret(chan, hit.declassify())`. Declassifying goes
+/// through `Secret::declassify`, so the "stats" feature counts it like any other.
+pub fn send_secret<E, P, T>(
+    chan: Chan<E, session_types::Send<T, P>>,
+    value: st::Secret<T, lat::Label_Empty>,
+) -> Chan<E, P>
+where
+    T: SecretValueSafe + std::marker::Send + 'static,
+{
+    chan.send(value.get_value_consume())
+}
+
+/// Receives a value and labels it `L`. Raising a public value's label never leaks anything.
+pub fn recv_secret<E, P, T, L>(chan: Chan<E, Recv<T, P>>) -> (Chan<E, P>, st::Secret<T, L>)
+where
+    T: SecretValueSafe + InvisibleSideEffectFree + std::marker::Send + 'static,
+    L: lat::Label,
+{
+    let (chan, value) = chan.recv();
+    (chan, value.classify())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secret_structs::secret_block;
+    use session_types::Eps;
+
+    #[test]
+    fn send_and_receive_secret_bool() {
+        session_types::connect(
+            |chan: Chan<(), Recv<bool, Eps>>| {
+                let (chan, hit) = recv_secret::<_, _, _, lat::Label_B>(chan);
+                chan.close();
+                assert!(hit.declassify().get_value_consume());
+            },
+            |chan: Chan<(), session_types::Send<bool, Eps>>| {
+                let hit: st::Secret<bool, lat::Label_A> = secret_block!(lat::Label_A { wrap_secret(true) });
+                send_secret(chan, hit.declassify()).close();
+            },
+        );
+    }
+}
@@ -1,11 +1,13 @@
+#![cfg_attr(feature = "audit_calls", feature(proc_macro_span))]
 use proc_macro::TokenStream;
 use proc_macro2::{Ident};
 use quote::{quote, ToTokens};
 use std::collections::HashSet;
 use std::{iter::FromIterator, str::FromStr};
-use syn::{parse_macro_input, spanned::Spanned, Data, DataStruct, DeriveInput, Expr, Fields, Type, Block, FieldValue, ExprField};
+use syn::{parse_macro_input, spanned::Spanned, Data, DataStruct, DeriveInput, Expr, Fields, Type, Block, FieldValue};
 use syn::parse::{Parse, ParseStream};
 use syn::token::Comma;
+use syn::visit_mut::VisitMut;
 
 struct LabeledBlock {
     ty: Type,
@@ -15,11 +17,215 @@ struct LabeledBlock {
 impl Parse for LabeledBlock {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let ty: Type = input.parse().unwrap_or_else(|_|{panic!("not a type")});
-        let blk: Block = input.parse().unwrap();
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let mut blk: Block = input.parse().unwrap();
+        for attr in attrs {
+            match attr.parse_meta()? {
+                syn::Meta::NameValue(nv) if nv.path.is_ident("arith") => match &nv.lit {
+                    syn::Lit::Str(mode) if mode.value() == "checked" => CheckedArith.visit_block_mut(&mut blk),
+                    _ => return Err(syn::Error::new(nv.lit.span(), "secret_macros: supported arith modes are: \"checked\"")),
+                },
+                meta => return Err(syn::Error::new(meta.span(), "secret_macros: unsupported secret block attribute")),
+            }
+        }
+        CapturedAssign::check(&blk)?;
         Ok(LabeledBlock {ty, blk})
     }
 }
 
+/** `let PAT = INIT else { ... };`. syn 1 doesn't know let-else and hands the whole statement over
+as an Expr::Verbatim, so it is parsed again here. */
+struct LetElse {
+    pat: syn::Pat,
+    ty: Option<Type>,
+    init: Expr,
+    diverge: Block,
+}
+
+impl Parse for LetElse {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<syn::Token![let]>()?;
+        let pat: syn::Pat = input.parse()?;
+        let ty = if input.peek(syn::Token![:]) {
+            input.parse::<syn::Token![:]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        input.parse::<syn::Token![=]>()?;
+        let init: Expr = input.parse()?;
+        input.parse::<syn::Token![else]>()?;
+        let diverge: Block = input.parse()?;
+        Ok(LetElse { pat, ty, init, diverge })
+    }
+}
+
+impl LetElse {
+    fn from_verbatim(expr: &Expr) -> Option<LetElse> {
+        match expr {
+            Expr::Verbatim(tokens) => syn::parse2(tokens.clone()).ok(),
+            _ => None,
+        }
+    }
+
+    // The initializer is parenthesized since it can't end in `}` before `else`
+    fn to_tokens_with(&self, init: proc_macro2::TokenStream, diverge: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let pat = &self.pat;
+        let ty = self.ty.as_ref().map(|ty| quote! { : #ty });
+        quote! {
+            let #pat #ty = (#init) else #diverge;
+        }
+    }
+}
+
+/** Finds assignments to variables captured from outside the block, such as `outer = x;`. These are
+rejected by the type checks anyway, but with a confusing trait error, so they are reported up front.
+Any name bound somewhere in the block counts as local, which may let a shadowed capture through to
+the type checks. Code inside unchecked_operation(...) and unsafe blocks is skipped. */
+#[derive(Default)]
+struct CapturedAssign {
+    locals: HashSet<String>,
+    collecting: bool,
+    error: Option<syn::Error>,
+}
+
+impl CapturedAssign {
+    fn check(blk: &Block) -> syn::Result<()> {
+        let mut blk = blk.clone();
+        let mut visitor = CapturedAssign { collecting: true, ..Default::default() };
+        visitor.visit_block_mut(&mut blk);
+        visitor.collecting = false;
+        visitor.visit_block_mut(&mut blk);
+        visitor.error.map_or(Ok(()), Err)
+    }
+
+    // The variable an assignment writes through, e.g. `a` for `a.b[i] = x` or `*a = x`
+    fn assigned_variable(lhs: &Expr) -> Option<&Ident> {
+        match lhs {
+            Expr::Path(p) if p.qself.is_none() => p.path.get_ident(),
+            Expr::Field(f) => CapturedAssign::assigned_variable(&f.base),
+            Expr::Index(i) => CapturedAssign::assigned_variable(&i.expr),
+            Expr::Paren(p) => CapturedAssign::assigned_variable(&p.expr),
+            Expr::Group(g) => CapturedAssign::assigned_variable(&g.expr),
+            Expr::Unary(syn::ExprUnary { op: syn::UnOp::Deref(_), expr, .. }) => CapturedAssign::assigned_variable(expr),
+            _ => None,
+        }
+    }
+
+    fn report(&mut self, lhs: &Expr) {
+        if let Some(var) = CapturedAssign::assigned_variable(lhs) {
+            if !self.locals.contains(&var.to_string()) {
+                let err = syn::Error::new(
+                    var.span(),
+                    format!(
+                        "secret_macros: cannot assign to `{}`, which is captured from outside the secret block, \
+                        since this could leak secret data; use unchecked_operation(...) if the assignment is intended",
+                        var
+                    ),
+                );
+                match &mut self.error {
+                    Some(e) => e.combine(err),
+                    None => self.error = Some(err),
+                }
+            }
+        }
+    }
+}
+
+impl VisitMut for CapturedAssign {
+    fn visit_pat_ident_mut(&mut self, pat: &mut syn::PatIdent) {
+        if self.collecting {
+            self.locals.insert(pat.ident.to_string());
+        }
+        syn::visit_mut::visit_pat_ident_mut(self, pat);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Some(mut let_else) = LetElse::from_verbatim(expr) {
+            self.visit_pat_mut(&mut let_else.pat);
+            self.visit_expr_mut(&mut let_else.init);
+            self.visit_block_mut(&mut let_else.diverge);
+            return;
+        }
+        match expr {
+            Expr::Call(call) if is_call_to(call, "unchecked_operation") => return,
+            Expr::Unsafe(_) => return,
+            Expr::Assign(a) if !self.collecting => self.report(&a.left),
+            Expr::AssignOp(a) if !self.collecting => self.report(&a.left),
+            _ => {}
+        }
+        syn::visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        visit_vec_macro_mut(self, mac);
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut syn::Item) {}
+}
+
+// Macro bodies are opaque to syn, so visit the elements of a `vec![...]`, the one macro secret
+// blocks accept besides secret_assert!, and write them back
+fn visit_vec_macro_mut<V: VisitMut>(visitor: &mut V, mac: &mut syn::Macro) {
+    if !mac.path.is_ident("vec") {
+        return;
+    }
+    if let Ok(mut elems) = vec_macro_elems(mac) {
+        for elem in elems.iter_mut() {
+            visitor.visit_expr_mut(elem);
+        }
+        mac.tokens = quote! { #elems };
+    }
+}
+
+/** Rewrites +, -, *, / (and their assignment forms) into calls to the SafeChecked* traits, so that
+integer overflow panics the same way in every profile. Nested items are left alone. */
+struct CheckedArith;
+
+impl CheckedArith {
+    fn checked_method(op: &syn::BinOp) -> Option<(&'static str, &'static str, bool)> {
+        match op {
+            syn::BinOp::Add(_) => Some(("SafeCheckedAdd", "safe_checked_add", false)),
+            syn::BinOp::Sub(_) => Some(("SafeCheckedSub", "safe_checked_sub", false)),
+            syn::BinOp::Mul(_) => Some(("SafeCheckedMul", "safe_checked_mul", false)),
+            syn::BinOp::Div(_) => Some(("SafeCheckedDiv", "safe_checked_div", false)),
+            syn::BinOp::AddEq(_) => Some(("SafeCheckedAdd", "safe_checked_add", true)),
+            syn::BinOp::SubEq(_) => Some(("SafeCheckedSub", "safe_checked_sub", true)),
+            syn::BinOp::MulEq(_) => Some(("SafeCheckedMul", "safe_checked_mul", true)),
+            syn::BinOp::DivEq(_) => Some(("SafeCheckedDiv", "safe_checked_div", true)),
+            _ => None,
+        }
+    }
+}
+
+impl VisitMut for CheckedArith {
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        visit_vec_macro_mut(self, mac);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        syn::visit_mut::visit_expr_mut(self, expr);
+        let (op, left, right) = match expr {
+            Expr::Binary(b) => (&b.op, &b.left, &b.right),
+            Expr::AssignOp(a) => (&a.op, &a.left, &a.right),
+            _ => return,
+        };
+        if let Some((imp, method, is_assign)) = CheckedArith::checked_method(op) {
+            let imp = Ident::new(imp, op.span());
+            let method = Ident::new(method, op.span());
+            // Must match the paths in is_call_to_allowlisted_function
+            let call = quote! { secret_structs::secret::#imp::#method(#left, #right) };
+            *expr = if is_assign {
+                syn::parse_quote! { #left = #call }
+            } else {
+                syn::parse_quote! { (#call) }
+            };
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut syn::Item) {}
+}
+
 #[proc_macro]
 pub fn secret_block(tokens: TokenStream) -> TokenStream {
     let LabeledBlock{ty, blk} = parse_macro_input!(tokens as LabeledBlock);
@@ -33,17 +239,11 @@ pub fn secret_block(tokens: TokenStream) -> TokenStream {
             || -> #ty { #blk }
         }.into(), true
     ).into();
-    quote::quote! {
-        if true {
-            ::secret_structs::secret::call_closure::<#ty, _, _>(
-                #executed_code
-            )
-        } else {
-            ::secret_structs::secret::call_closure::<#ty, _, _>(
-                #checking_code
-            )
-        }
-    }.into()
+    select_executed_or_checking(
+        quote::quote! { ::secret_structs::secret::call_closure::<#ty, _, _> },
+        executed_code,
+        checking_code,
+    ).into()
 }
 
 #[proc_macro]
@@ -59,17 +259,50 @@ pub fn secret_block_no_return(tokens: TokenStream) -> TokenStream {
             || -> #ty { #blk }
         }.into(), true
     ).into();
-    quote::quote! {
-        if true {
-            ::secret_structs::secret::call_closure_no_return::<#ty, _>(
+    select_executed_or_checking(
+        quote::quote! { ::secret_structs::secret::call_closure_no_return::<#ty, _> },
+        executed_code,
+        checking_code,
+    ).into()
+}
+
+/** Emits `if true { call(executed) } else { call(checking) }`. The else branch never runs but is
+still type-checked and monomorphized, which is what enforces the secret block restrictions.
+With the `trust_blocks` feature in release builds, only the executed closure is emitted; this
+skips the checks, so it is only sound for code that also builds without the feature. */
+fn select_executed_or_checking(
+    call: proc_macro2::TokenStream,
+    executed_code: proc_macro2::TokenStream,
+    checking_code: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let selected = if cfg!(feature = "trust_blocks") && !cfg!(debug_assertions) {
+        quote::quote! {
+            #call(
                 #executed_code
             )
-        } else {
-            ::secret_structs::secret::call_closure_no_return::<#ty, _>(
-                #checking_code
-            )
         }
-    }.into()
+    } else {
+        quote::quote! {
+            if true {
+                #call(
+                    #executed_code
+                )
+            } else {
+                #call(
+                    #checking_code
+                )
+            }
+        }
+    };
+    // The expansion nests unsafe blocks, e.g., when a secret block is used inside an unsafe fn or
+    // another block's expansion. Lint attributes can't go on an expression, but they can go on a let.
+    quote::quote! {
+        {
+            #[allow(unused_unsafe)]
+            let secret_block_result = #selected;
+            secret_block_result
+        }
+    }
 }
 
 fn secret_block_backend_helper(input: TokenStream, is_duplicate: bool) -> TokenStream {
@@ -125,7 +358,7 @@ fn secret_block_backend_helper(input: TokenStream, is_duplicate: bool) -> TokenS
 
 // Returns if the function call is white-listed.
 fn is_call_to_allowlisted_function(call: &syn::ExprCall) -> bool {
-    let allowed_functions = HashSet::from([
+    let mut allowed_functions = HashSet::from([
         "char::is_digit".to_string(),
         "core::primitive::str::len".to_string(),
         "std::arch::x86_64::_mm256_add_pd".to_string(),
@@ -139,26 +372,45 @@ fn is_call_to_allowlisted_function(call: &syn::ExprCall) -> bool {
         "std::arch::x86_64::_mm256_set1_pd".to_string(),
         "std::arch::x86_64::_mm256_store_pd".to_string(),
         "std::arch::x86_64::_mm256_sub_pd".to_string(),
+        "std::boxed::Box::new".to_string(),
         "std::clone::Clone::clone".to_string(),
-        "std::cmp::min".to_string(),
         "std::fs::File::open".to_string(),
         "std::iter::Copied::cycle".to_string(),
         "std::iter::Iterator::by_ref".to_string(),
+        "std::iter::Iterator::chain".to_string(),
+        "std::iter::Iterator::flatten".to_string(),
         "std::iter::Iterator::next".to_string(),
+        "std::iter::Iterator::rev".to_string(),
+        "std::iter::Iterator::skip".to_string(),
+        "std::iter::Iterator::step_by".to_string(),
         "std::iter::Iterator::take".to_string(),
         "std::iter::zip".to_string(),
+        // As everywhere else, the caller of assume_init is responsible for the value being initialized.
+        "std::mem::MaybeUninit::as_mut_ptr".to_string(),
+        "std::mem::MaybeUninit::as_ptr".to_string(),
         "std::mem::MaybeUninit::assume_init".to_string(),
+        "std::mem::MaybeUninit::new".to_string(),
         "std::mem::MaybeUninit::uninit".to_string(),
+        "std::mem::MaybeUninit::write".to_string(),
+        "std::mem::MaybeUninit::zeroed".to_string(),
         "std::mem::transmute".to_string(),
         "std::primitive::f64::sqrt".to_string(),
         "core::primitive::u32::is_power_of_two".to_string(),
         "std::option::Option::Some".to_string(),
         "std::option::Option::unwrap".to_string(),
         "std::slice::Iter::copied".to_string(),
+        "std::string::String::as_str".to_string(),
         "std::string::String::clear".to_string(),
         "std::string::String::from".to_string(),
+        "std::string::String::insert".to_string(),
+        "std::string::String::is_empty".to_string(),
         "std::string::String::len".to_string(),
         "std::string::String::clone".to_string(),
+        "std::string::String::pop".to_string(),
+        "std::string::String::push".to_string(),
+        "std::string::String::push_str".to_string(),
+        "std::vec::Vec::append".to_string(),
+        "std::vec::Vec::as_slice".to_string(),
         "std::vec::Vec::clear".to_string(),
         "std::vec::Vec::clone".to_string(),
         "std::vec::Vec::extend_from_slice".to_string(),
@@ -175,9 +427,15 @@ fn is_call_to_allowlisted_function(call: &syn::ExprCall) -> bool {
         "str::trim".to_string(),
         "usize::to_string".to_string(),
         "<[T]>::sort".to_string(),
+        "<[_]>::chunks".to_string(),
+        "<[_]>::chunks_exact".to_string(),
         "<[_]>::copy_from_slice".to_string(),
+        "<[_]>::get".to_string(),
         "<[_]>::iter".to_string(),
         "<[_]>::len".to_string(),
+        "<[_]>::split_at".to_string(),
+        "<[_]>::split_first".to_string(),
+        "<[_]>::windows".to_string(),
         "secret_structs::secret::SafeAdd::safe_add".to_string(),
         "secret_structs::secret::SafeSub::safe_sub".to_string(),
         "secret_structs::secret::SafeNot::safe_not".to_string(),
@@ -196,18 +454,241 @@ fn is_call_to_allowlisted_function(call: &syn::ExprCall) -> bool {
         "secret_structs::secret::SafeSubAssign::safe_sub_assign".to_string(),
         "secret_structs::secret::SafeMulAssign::safe_mul_assign".to_string(),
         "secret_structs::secret::SafeDivAssign::safe_div_assign".to_string(),
+        "secret_structs::secret::SafeCheckedAdd::safe_checked_add".to_string(),
+        "secret_structs::secret::SafeCheckedSub::safe_checked_sub".to_string(),
+        "secret_structs::secret::SafeCheckedMul::safe_checked_mul".to_string(),
+        "secret_structs::secret::SafeCheckedDiv::safe_checked_div".to_string(),
         // Add other allowed functions here.
     ]);
+    // Integer to/from string conversions are pure
+    for int in ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"] {
+        allowed_functions.insert(format!("{}::to_string", int));
+        allowed_functions.insert(format!("{}::from_str_radix", int));
+        allowed_functions.insert(format!("<{} as std::str::FromStr>::from_str", int));
+    }
 
     if let syn::Expr::Path(path_expr) = &*call.func {
         let mut path_str = quote::quote! {#path_expr}.to_string();
         path_str.retain(|c| !c.is_whitespace());
-        allowed_functions.contains(&path_str)
+        // Entries may be written with spaces, e.g., `<i32 as std::str::FromStr>::from_str`
+        allowed_functions.iter().any(|f| f.split_whitespace().collect::<String>() == path_str)
     } else {
         false
     }
 }
 
+/** The elements of a `vec![a, b, c]` in a secret block, which is rebuilt from the expanded or
+checked elements. The repeat form `vec![value; length]` clones the value, possibly through a user
+Clone impl, so it's rejected. */
+fn vec_macro_elems(mac: &syn::Macro) -> syn::Result<syn::punctuated::Punctuated<Expr, Comma>> {
+    mac.parse_body_with(|input: ParseStream| {
+        let mut elems = syn::punctuated::Punctuated::new();
+        while !input.is_empty() {
+            elems.push_value(input.parse()?);
+            if input.peek(syn::Token![;]) {
+                return Err(input.error("vec![value; length] isn't supported in secret blocks; push the elements instead"));
+            }
+            if input.is_empty() {
+                break;
+            }
+            elems.push_punct(input.parse()?);
+        }
+        Ok(elems)
+    })
+}
+
+/** Calls an allowlisted `str` method written with method syntax, e.g., `s.trim().to_string()`,
+directly as `str::trim(SafeAsStr::safe_as_str(&(s)))` instead of expecting a Vetted result. The
+macro can't see the receiver's type, so only `str` methods are routed: String and &str receivers
+implement SafeAsStr, and any other receiver (e.g., a user type with its own `trim`) fails to
+type-check rather than being trusted. Returns the call and its path, for the audit log. */
+fn allowlisted_method_call(
+    method_call: &syn::ExprMethodCall,
+    receiver: &proc_macro2::TokenStream,
+    args: &proc_macro2::TokenStream,
+) -> Option<(proc_macro2::TokenStream, syn::ExprPath)> {
+    let method = &method_call.method;
+    let path: syn::ExprPath = syn::parse_quote! { str::#method };
+    let call = syn::ExprCall {
+        attrs: vec![],
+        func: Box::new(syn::Expr::Path(path.clone())),
+        paren_token: Default::default(),
+        args: Default::default(),
+    };
+    if !is_call_to_allowlisted_function(&call) {
+        return None;
+    }
+    let turbofish = &method_call.turbofish;
+    let args = if args.is_empty() { quote! {} } else { quote! { , #args } };
+    let receiver = quote! { ::secret_structs::secret::SafeAsStr::safe_as_str(&(#receiver)) };
+    Some((quote! { str::#method#turbofish(#receiver #args) }, path))
+}
+
+// Routes iterator method calls (`.map(f)`, `.collect()`, `.cloned()`, `.copied()`, `.iter()`,
+// `.into_iter()` and consumers like `.sum()` and `.any(f)`) through secret::SafeIterator, SafeIter
+// and SafeIntoIterator, which are only implemented for iterators and collections whose methods have
+// no hidden side effects. `.iter()` and `.into_iter()` keep method syntax so that the receiver is
+// auto-dereferenced, and the consumers taking `&mut self` so that it is auto-borrowed.
+fn safe_iterator_method_call(
+    method_call: &syn::ExprMethodCall,
+    receiver: &proc_macro2::TokenStream,
+    args: &proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    let turbofish = &method_call.turbofish;
+    match method_call.method.to_string().as_str() {
+        "map" if method_call.args.len() == 1 && turbofish.is_none() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_map(#receiver, #args)
+        }),
+        "collect" if method_call.args.is_empty() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_collect#turbofish(#receiver)
+        }),
+        "cloned" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_cloned(#receiver)
+        }),
+        "copied" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_copied(#receiver)
+        }),
+        "sum" if method_call.args.is_empty() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_sum#turbofish(#receiver)
+        }),
+        "product" if method_call.args.is_empty() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_product#turbofish(#receiver)
+        }),
+        // With no arguments these can't be Ord::max/Ord::min
+        "max" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_max(#receiver)
+        }),
+        "min" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_min(#receiver)
+        }),
+        "count" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_count(#receiver)
+        }),
+        "last" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            ::secret_structs::secret::SafeIterator::safe_last(#receiver)
+        }),
+        "position" if method_call.args.len() == 1 && turbofish.is_none() => Some(quote::quote! {
+            { use ::secret_structs::secret::SafeIterator as _; (#receiver).safe_position(#args) }
+        }),
+        "find" if method_call.args.len() == 1 && turbofish.is_none() => Some(quote::quote! {
+            { use ::secret_structs::secret::SafeIterator as _; (#receiver).safe_find(#args) }
+        }),
+        "any" if method_call.args.len() == 1 && turbofish.is_none() => Some(quote::quote! {
+            { use ::secret_structs::secret::SafeIterator as _; (#receiver).safe_any(#args) }
+        }),
+        "all" if method_call.args.len() == 1 && turbofish.is_none() => Some(quote::quote! {
+            { use ::secret_structs::secret::SafeIterator as _; (#receiver).safe_all(#args) }
+        }),
+        "iter" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            { use ::secret_structs::secret::SafeIter as _; (#receiver).safe_iter() }
+        }),
+        "into_iter" if method_call.args.is_empty() && turbofish.is_none() => Some(quote::quote! {
+            { use ::secret_structs::secret::SafeIntoIterator as _; (#receiver).safe_into_iter() }
+        }),
+        _ => None,
+    }
+}
+
+/** Routes `Default::default()` and `T::default()` (for a type-like, capitalized T) through the
+Default trait, so an inherent `default` function, which may have side effects, is never called.
+A user Default impl may have side effects too, so T must be secret::SafeDefault. */
+fn default_call(call: &syn::ExprCall) -> Option<proc_macro2::TokenStream> {
+    let path = match &*call.func {
+        syn::Expr::Path(path_expr) if path_expr.qself.is_none() && call.args.is_empty() => &path_expr.path,
+        _ => return None,
+    };
+    let segments: Vec<&syn::PathSegment> = path.segments.iter().collect();
+    let (last, prefix) = segments.split_last()?;
+    if last.ident != "default" || !last.arguments.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = prefix.iter().map(|s| s.ident.to_string()).collect();
+    let names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    match names.as_slice() {
+        ["Default"] | ["std", "default", "Default"] | ["core", "default", "Default"] => Some(quote::quote! {
+            ::secret_structs::secret::safe_default()
+        }),
+        // The path only pins down T (generic arguments may be inferred); it's named, never called
+        [.., ty] if ty.starts_with(char::is_uppercase) => Some(quote::quote! {
+            ::secret_structs::secret::trait_default(#path)
+        }),
+        _ => None,
+    }
+}
+
+/** Routes `std::cmp::max`/`min` and `Ord::max`/`min`/`clamp` through secret::ord_max and friends.
+These are generic over Ord, so called directly they would run a user `cmp`, which may have side
+effects; the replacements require SafeOrd, which only built-in types implement. */
+fn ord_call(call: &syn::ExprCall, args: &proc_macro2::TokenStream) -> Option<proc_macro2::TokenStream> {
+    let path = match &*call.func {
+        syn::Expr::Path(path_expr) if path_expr.qself.is_none() => &path_expr.path,
+        _ => return None,
+    };
+    if path.segments.iter().any(|s| !s.arguments.is_empty()) {
+        return None;
+    }
+    let names: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    let (name, prefix) = names.split_last()?;
+    let is_cmp_fn = matches!(prefix, ["std", "cmp"] | ["core", "cmp"] | ["cmp"]);
+    let is_ord_method = matches!(prefix, ["std", "cmp", "Ord"] | ["core", "cmp", "Ord"] | ["cmp", "Ord"] | ["Ord"]);
+    let helper = match *name {
+        "max" if is_cmp_fn || is_ord_method => quote! { ord_max },
+        "min" if is_cmp_fn || is_ord_method => quote! { ord_min },
+        "clamp" if is_ord_method => quote! { ord_clamp },
+        _ => return None,
+    };
+    Some(quote! { ::secret_structs::secret::#helper(#args) })
+}
+
+/** With the `audit_calls` feature, appends a `kind<TAB>callee<TAB>file:line:col` line for each call
+that the checking closure trusts (an allowlisted function, or a function or method returning Vetted)
+to the file named by SECRET_MACROS_AUDIT_FILE. Nothing is written if it isn't set. Lines already in
+the file aren't appended again, so a rebuild doesn't duplicate them. */
+#[cfg(feature = "audit_calls")]
+fn audit_call<C: ToTokens + Spanned>(kind: &str, callee: &C) {
+    use std::io::Write;
+    // Checking code is generated more than once for some expressions, and earlier builds may have
+    // listed a call already. Loaded from the file on first use.
+    static RECORDED: std::sync::Mutex<Option<Vec<String>>> = std::sync::Mutex::new(None);
+
+    let path = match std::env::var("SECRET_MACROS_AUDIT_FILE") {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+    let mut callee_str = quote::quote! {#callee}.to_string();
+    callee_str.retain(|c| !c.is_whitespace());
+    let span = callee.span().unwrap();
+    let start = span.start();
+    let line = format!(
+        "{}\t{}\t{}:{}:{}\n",
+        kind,
+        callee_str,
+        span.source_file().path().display(),
+        start.line,
+        start.column + 1
+    );
+    let mut recorded = RECORDED.lock().unwrap_or_else(|err| err.into_inner());
+    let recorded = recorded.get_or_insert_with(|| {
+        std::fs::read_to_string(&path)
+            .map(|text| text.lines().map(|line| format!("{}\n", line)).collect())
+            .unwrap_or_default()
+    });
+    if recorded.contains(&line) {
+        return;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .unwrap_or_else(|err| panic!("secret_macros: cannot write audit file {}: {}", path, err));
+    recorded.push(line);
+}
+
+#[cfg(not(feature = "audit_calls"))]
+fn audit_call<C: ToTokens + Spanned>(_kind: &str, _callee: &C) {}
+
 // Returns whether the function call is a specific function.
 fn is_call_to(call: &syn::ExprCall, path: &str) -> bool {
     if let syn::Expr::Path(path_expr) = &*call.func {
@@ -261,6 +742,10 @@ fn expand_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>) -> proc_macr
                 } else {
                     quote::quote! {compile_error!("unchecked_operation needs an operation.");}
                 }
+            } else if let Some(default) = default_call(expr_call) {
+                default
+            } else if let Some(ord) = ord_call(expr_call, &args) {
+                ord
             } else if is_call_to_allowlisted_function(expr_call) {
                 let func = &*expr_call.func;
                 quote::quote! {
@@ -275,6 +760,33 @@ fn expand_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>) -> proc_macr
             }
         }
         syn::Expr::Continue(continue_stmt) => continue_stmt.into_token_stream(),
+        // Only compiled into the caller's debug builds, so release builds never branch on the condition
+        syn::Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("secret_assert") => {
+            match expr_macro.mac.parse_body::<Expr>() {
+                Ok(cond) => {
+                    let msg = format!("secret_assert!({}) failed", cond.to_token_stream());
+                    let cond = expand_expr(&cond, secrecy_label);
+                    quote::quote! {
+                        {
+                            #[cfg(debug_assertions)]
+                            {
+                                if !(#cond) {
+                                    ::secret_structs::secret::secret_assert_failed(#msg)
+                                }
+                            }
+                        }
+                    }
+                }
+                Err(err) => err.to_compile_error(),
+            }
+        }
+        syn::Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("vec") => match vec_macro_elems(&expr_macro.mac) {
+            Ok(elems) => {
+                let elements = comma_separate(elems.iter().map(|expr| expand_expr(expr, secrecy_label)));
+                quote::quote! { ::std::vec![#elements] }
+            }
+            Err(err) => err.to_compile_error(),
+        },
         syn::Expr::Macro(_) => quote::quote! { compile_error!("Function calls & macros are not allowed in secret blocks.") },
         syn::Expr::Binary(expr_binary) => {
             // Check the left-hand side of the expression, and the right-hand side.
@@ -337,30 +849,35 @@ fn expand_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>) -> proc_macr
             ));
             let method = &method_call_expr.method;
             let turbofish = &method_call_expr.turbofish;
+            if let Some(safe_call) = safe_iterator_method_call(method_call_expr, &receiver, &args) {
+                return safe_call;
+            }
+            if let Some((call, _)) = allowlisted_method_call(method_call_expr, &receiver, &args) {
+                return quote::quote! { (#call) };
+            }
             // TODO: Shouldn't evaluate #args inside of unsafe block
             quote::quote! {
-                (unsafe { (#receiver.#method#turbofish(#args) as ::secret_structs::secret::Vetted<_>).unwrap() })
+                (unsafe { ((#receiver).#method#turbofish(#args) as ::secret_structs::secret::Vetted<_>).unwrap() })
             }
         }
         syn::Expr::Lit(expr_lit) => expr_lit.into_token_stream(),
         syn::Expr::Field(field_access) => {
-            let e: Expr  = syn::parse2(expand_expr(&(field_access.base), secrecy_label)).expect("ErrS");
-            let e2: Expr = syn::parse2(quote::quote!{ (#e) }).expect("ErrS");
-            let e3: Box<Expr> = Box::new(e2);
-            let f_new = ExprField {
-                attrs: field_access.attrs.clone(),
-                base: e3,
-                dot_token: field_access.dot_token.clone(),
-                member: field_access.member.clone()
-            };
+            // Parenthesize the base as tokens rather than re-parsing it, which can fail for some shapes
+            let e = expand_expr(&(field_access.base), secrecy_label);
+            let member = &field_access.member;
+            let f_new = quote::quote! { (#e).#member };
             f_new.into_token_stream()
         }
         syn::Expr::Path(path_access) => path_access.into_token_stream(),
         syn::Expr::Paren(paren_expr) => {
             let interal_expr = expand_expr(&paren_expr.expr, secrecy_label);
-            let mut new_paren_expr = paren_expr.clone();
-            new_paren_expr.expr = Box::new(syn::parse2(interal_expr).unwrap());
-            new_paren_expr.into_token_stream()
+            quote::quote! { (#interal_expr) }
+        }
+        // Invisible groups come from macro_rules metavariables (e.g., $e:expr). Parenthesize to
+        // keep the grouping, since the expansion is emitted as plain tokens.
+        syn::Expr::Group(group_expr) => {
+            let interal_expr = expand_expr(&group_expr.expr, secrecy_label);
+            quote::quote! { (#interal_expr) }
         }
         syn::Expr::Struct(struct_literal) => {
             let fields: syn::punctuated::Punctuated<FieldValue, Comma> = {
@@ -510,6 +1027,9 @@ fn expand_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>) -> proc_macr
                 #expr as #ty
             }
         }
+        syn::Expr::Async(_) | syn::Expr::Await(_) => quote::quote_spanned! {expr.span()=>
+            compile_error!("async/await is not permitted in secret blocks because suspension is an observable effect")
+        },
         // TODO: Handle the other kinds of expressions
         expr => {
             let expr_display = proc_macro2::TokenStream::to_string(&quote! {#expr});
@@ -578,6 +1098,11 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
                 } else {
                     quote::quote! {compile_error!("unchecked_operation needs an operation.");}
                 }
+            } else if let Some(default) = default_call(expr_call) {
+                make_check_secret_block_safe(default, do_sbs_check)
+            } else if let Some(ord) = ord_call(expr_call, &args) {
+                audit_call("allowlisted", &*expr_call.func);
+                make_check_secret_block_safe(ord, do_sbs_check)
             } else if is_call_to_allowlisted_function(expr_call) {
                 let args = comma_separate(expr_call.args.iter().map(
                     |arg: &syn::Expr| -> proc_macro2::TokenStream {
@@ -585,6 +1110,7 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
                     },
                 ));
                 let func = &*expr_call.func;
+                audit_call("allowlisted", func);
                 make_check_secret_block_safe(quote::quote! { #func(#args) }, do_sbs_check)
             } else {
                 let args = comma_separate(expr_call.args.iter().map(
@@ -593,12 +1119,32 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
                     },
                 ));
                 let func = &*expr_call.func;
+                audit_call("vetted", func);
                 // An side_effect_free_attr function must return a InvisibleSideEffectFree type
                 // TODO: Shouldn't evaluate #args inside of unsafe block
                 /*make_check_secret_block_safe(*/quote::quote! { unsafe {(#func(#args) as ::secret_structs::secret::Vetted<_>).unwrap() } }/*, do_sbs_check)*/
             }
         }
         syn::Expr::Continue(continue_stmt) => continue_stmt.into_token_stream(),
+        syn::Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("secret_assert") => {
+            match expr_macro.mac.parse_body::<Expr>() {
+                Ok(cond) => {
+                    let cond = check_expr(&cond, secrecy_label, true);
+                    quote::quote! {
+                        { let _: bool = #cond; }
+                    }
+                }
+                Err(err) => err.to_compile_error(),
+            }
+        }
+        // Each element is checked like an array element, so a non-InvisibleSideEffectFree one is rejected
+        syn::Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("vec") => match vec_macro_elems(&expr_macro.mac) {
+            Ok(elems) => {
+                let elements = comma_separate(elems.iter().map(|expr| check_expr(expr, secrecy_label, true)));
+                make_check_secret_block_safe(quote::quote! { ::std::vec![#elements] }, do_sbs_check)
+            }
+            Err(err) => err.to_compile_error(),
+        },
         // TODO: Handle macros better. I think you can look at their token stream to get their expansion?
         syn::Expr::Macro(_) => quote::quote! { compile_error!("Function calls & macros are not allowed in secret blocks.") },
         syn::Expr::Binary(expr_binary) => {
@@ -661,6 +1207,13 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
                     let rhs = check_expr(&*expr_binary.right, secrecy_label, false);
                     quote::quote! { ::secret_structs::secret::SafeBitAnd::safe_bitand(#lhs, #rhs) }
                 }
+
+                syn::BinOp::BitOr(_) => {
+                    // Outer SBS checks not needed because expressions have built-in types (well, once we disallow overloading)
+                    let lhs = check_expr(&expr_binary.left, secrecy_label, false);
+                    let rhs = check_expr(&expr_binary.right, secrecy_label, false);
+                    quote::quote! { ::secret_structs::secret::SafeBitOr::safe_bitor(#lhs, #rhs) }
+                }
                 // Disallows all other binary operators
                 _op => {
                     let expr_display = proc_macro2::TokenStream::to_string(&quote! {#expr_binary});
@@ -710,12 +1263,18 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
         }
         syn::Expr::Assign(assign_expr) => {
             // Set do_sbs_check for LHS of assignments, since it's an lvalue, not an rvalue
-            let lhs: proc_macro2::TokenStream = check_expr(&assign_expr.left, secrecy_label, false).into();
+            let lhs: proc_macro2::TokenStream = match &*assign_expr.left {
+                syn::Expr::Index(idx) => check_index_mut(idx, secrecy_label),
+                left => {
+                    let left = check_expr(left, secrecy_label, false);
+                    quote::quote! { &mut #left }
+                }
+            };
             let rhs: proc_macro2::TokenStream =
                 check_expr(&assign_expr.right, secrecy_label, true).into();
             make_check_secret_block_safe(
                 quote::quote!{
-                    *::secret_structs::secret::not_mut_secret(&mut #lhs) = #rhs
+                    *::secret_structs::secret::not_mut_secret(#lhs) = #rhs
                 },
                 do_sbs_check
             )
@@ -724,7 +1283,10 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
             let op = assign_op_expr.op;
 
             // Outer SBS checks not needed because expressions have built-in types
-            let new_expr_left: proc_macro2::TokenStream = check_expr(&make_mut_ref(*assign_op_expr.left.clone()), secrecy_label, false);
+            let new_expr_left: proc_macro2::TokenStream = match &*assign_op_expr.left {
+                syn::Expr::Index(idx) => check_index_mut(idx, secrecy_label),
+                left => check_expr(&make_mut_ref(left.clone()), secrecy_label, false),
+            };
             let new_expr_right = check_expr(&assign_op_expr.right, secrecy_label, false);
 
             match op {
@@ -771,11 +1333,21 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
             ));
             let method = &method_call_expr.method;
             let turbofish = &method_call_expr.turbofish;
+            if let Some(safe_call) = safe_iterator_method_call(method_call_expr, &receiver, &args) {
+                return safe_call;
+            }
+            if let Some((call, path)) = allowlisted_method_call(method_call_expr, &receiver, &args) {
+                audit_call("allowlisted", &path);
+                return make_check_secret_block_safe(quote::quote! { (#call) }, do_sbs_check);
+            }
 
+            audit_call("vetted_method", method);
             // Don't need an outer check since side_effect_free_attr methods are guaranteed to be InvisibleSideEffectFree
+            // Same receiver syntax as expand_expr, so auto-ref and moves resolve the same in both paths.
+            // The receiver is a copy here, so &mut self methods and by-value self methods only touch the copy.
             // TODO: Shouldn't evaluate #args inside of unsafe block
             /*make_check_secret_block_safe(*/quote::quote! {
-                ((&#receiver).#method#turbofish(#args) as ::secret_structs::secret::Vetted<_>).unwrap()
+                (unsafe { ((#receiver).#method#turbofish(#args) as ::secret_structs::secret::Vetted<_>).unwrap() })
             }/*, do_sbs_check)*/
         }
         // Literals don't need checks
@@ -785,28 +1357,25 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
             e
         }
         syn::Expr::Field(field_access) => {
-            let e: Expr  = syn::parse2(check_expr(&*(field_access.base), secrecy_label, true)).expect("ErrS");
-            let e2: Expr = syn::parse2(quote::quote!{ (#e) }).expect("ErrS");
-            let e3: Box<Expr> = Box::new(e2);
-            let f_new = ExprField {
-                attrs: field_access.attrs.clone(),
-                base: e3,
-                dot_token: field_access.dot_token.clone(),
-                member: field_access.member.clone()
-            };
+            // Parenthesize the base as tokens rather than re-parsing it, which can fail for some shapes
+            let e = check_expr(&field_access.base, secrecy_label, true);
+            let member = &field_access.member;
+            let f_new = quote::quote! { (#e).#member };
             // Don't need check around whole expression because e.f is InvisibleSideEffectFree if e is
             f_new.into_token_stream()
         }
         syn::Expr::Paren(paren_expr) => {
             let interal_expr = check_expr(&paren_expr.expr, secrecy_label, do_sbs_check);
-            let mut new_paren_expr = paren_expr.clone();
-            new_paren_expr.expr = Box::new(syn::parse2(interal_expr).unwrap());
-            new_paren_expr.into_token_stream()
+            quote::quote! { (#interal_expr) }
+        }
+        syn::Expr::Group(group_expr) => {
+            let interal_expr = check_expr(&group_expr.expr, secrecy_label, do_sbs_check);
+            quote::quote! { (#interal_expr) }
         }
         // fix_sbs_checking: Path (e.g., an identifier) needs a check because VisibleSideEffectFree doesn't exclude all non-InvisibleSideEffectFree types from being captured
         syn::Expr::Path(path_access) => {
             let p = path_access.into_token_stream();
-            make_check_secret_block_safe_ptr_read(p, do_sbs_check)
+            make_check_secret_block_safe_ptr_read(p, path_access.span(), do_sbs_check)
             //p
         }
         // Duplicate - commenting out in favor of latter case
@@ -945,6 +1514,12 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
         syn::Expr::Repeat(repeat_expr) => {
             // An expression of the form `[value; length]`.
             // We don't need to actually check the length, since it must be a constant expression.
+            // Say so up front rather than leave a confusing type error for a runtime length.
+            if !is_const_len(&repeat_expr.len) {
+                return quote::quote_spanned! {repeat_expr.len.span()=>
+                    compile_error!("array repeat length must be a public constant")
+                };
+            }
             let expr = check_expr(&repeat_expr.expr, secrecy_label, true);
             let mut new_repeat_expr = repeat_expr.clone();
             new_repeat_expr.expr = Box::new(syn::parse2(expr).unwrap());
@@ -960,14 +1535,7 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
             new_return_expr.expr = Some(Box::new(syn::parse2(expr).unwrap()));
             new_return_expr.into_token_stream()
         }
-        syn::Expr::Index(idx) => {
-            // Outer expressions don't need checks since the arguments of safe_index must be built-in types
-            let new_idx_expr: proc_macro2::TokenStream = check_expr(&idx.expr, secrecy_label, false);
-            let new_idx_index = check_expr(&idx.index, secrecy_label, false);
-            quote::quote! {
-                ::secret_structs::secret::check_safe_index_expr(#new_idx_expr)[::secret_structs::secret::check_safe_index(#new_idx_index)]
-            }
-        }
+        syn::Expr::Index(idx) => check_index(idx, secrecy_label, false),
         syn::Expr::Tuple(tuple) => {
             let args = comma_separate(
                 tuple
@@ -986,7 +1554,8 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
                     let new_operand_expr = check_expr(&*unary.expr, secrecy_label, true);
                     // Outer expression doesn't need a check since operand must be InvisibleSideEffectFree
                     // make_check_secret_block_safe(quote::quote! { #operator(#operand) }, do_sbs_check)
-                    quote::quote! { #operator(#new_operand_expr) }
+                    // The operand must not be a raw pointer (those need unchecked_operation)
+                    quote::quote! { #operator(::secret_structs::secret::check_safe_deref(#new_operand_expr)) }
                 }
                 syn::UnOp::Not(_) => {
                     // Expressions don't need InvisibleSideEffectFree checks because they're built-in types
@@ -1004,7 +1573,10 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
         syn::Expr::Reference(reference) => {
             // fix_sbs_checking: do_sbs_check: true -> false because reference.expr will be checked below
             // TODO: Why put the check around &e instead of putting it around e?
-            let operand = check_expr(&*reference.expr, secrecy_label, false);
+            let operand = match (&*reference.expr, reference.mutability) {
+                (syn::Expr::Index(idx), Some(_)) => check_index(idx, secrecy_label, true),
+                (operand, _) => check_expr(operand, secrecy_label, false),
+            };
             match reference.mutability {
                 Some(_) => {
                     // fix_sbs_checking: We need a check around this expression because VisibleSideEffectFree doesn't exclude all non-SBS types from being captured
@@ -1023,6 +1595,9 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
             let ty = &cast.ty;
             make_check_secret_block_safe(quote::quote! { #expr as #ty }, do_sbs_check)
         }
+        syn::Expr::Async(_) | syn::Expr::Await(_) => quote::quote_spanned! {expr.span()=>
+            compile_error!("async/await is not permitted in secret blocks because suspension is an observable effect")
+        },
         // TODO: Handle the other kinds of expressions
         expr => {
             let expr_display = proc_macro2::TokenStream::to_string(&quote! {#expr});
@@ -1045,6 +1620,70 @@ fn check_expr(expr: &syn::Expr, secrecy_label: &Option<syn::Type>, do_sbs_check:
     }
 }
 
+// Checks e[i]. mutable is set when the indexed place is assigned to or borrowed mutably.
+fn check_index(idx: &syn::ExprIndex, secrecy_label: &Option<syn::Type>, mutable: bool) -> proc_macro2::TokenStream {
+    // Outer expressions don't need checks since the arguments of safe_index must be built-in types
+    let new_idx_expr = match &*idx.expr {
+        // For e[i][j], e[i] is a place that can't be moved into check_safe_index_expr, so its
+        // type is checked through a reference of the same mutability instead.
+        syn::Expr::Index(inner) => {
+            let inner_expr = check_index(inner, secrecy_label, mutable);
+            if mutable {
+                quote::quote! { (*::secret_structs::secret::check_safe_index_expr(&mut #inner_expr)) }
+            } else {
+                quote::quote! { (*::secret_structs::secret::check_safe_index_expr(&#inner_expr)) }
+            }
+        }
+        expr => {
+            let new_expr = check_expr(expr, secrecy_label, false);
+            quote::quote! { ::secret_structs::secret::check_safe_index_expr(#new_expr) }
+        }
+    };
+    let new_idx_index = check_expr(&idx.index, secrecy_label, false);
+    quote::quote! {
+        #new_idx_expr[::secret_structs::secret::check_safe_index(#new_idx_index)]
+    }
+}
+
+// Checks the target of an assignment to e[i] and evaluates to a `&mut` to the element. Goes through
+// SafeIndexMut with method syntax, so a `&mut` base like `v` in `let v = unwrap_secret_mut_ref(..)`
+// is reborrowed and can be assigned through more than once.
+fn check_index_mut(idx: &syn::ExprIndex, secrecy_label: &Option<syn::Type>) -> proc_macro2::TokenStream {
+    let base = match &*idx.expr {
+        syn::Expr::Index(inner) => {
+            let inner_ref = check_index_mut(inner, secrecy_label);
+            quote::quote! { (*#inner_ref) }
+        }
+        expr => check_expr(expr, secrecy_label, false),
+    };
+    let index = check_expr(&idx.index, secrecy_label, false);
+    quote::quote! {
+        {
+            use ::secret_structs::secret::SafeIndexMut as _;
+            (#base).safe_index_mut(::secret_structs::secret::check_safe_index(#index))
+        }
+    }
+}
+
+// Whether an array repeat length looks like a constant expression: integer literals, constants
+// (by the naming convention that they're upper case, which also covers const generics like N),
+// and arithmetic and casts on those.
+fn is_const_len(len: &syn::Expr) -> bool {
+    match len {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(_), .. }) => true,
+        syn::Expr::Path(path) => path.qself.is_none() && path.path.segments.last().map_or(false, |seg| {
+            let name = seg.ident.to_string();
+            name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        }),
+        syn::Expr::Paren(paren) => is_const_len(&paren.expr),
+        syn::Expr::Group(group) => is_const_len(&group.expr),
+        syn::Expr::Binary(binary) => is_const_len(&binary.left) && is_const_len(&binary.right),
+        syn::Expr::Unary(unary) => is_const_len(&unary.expr),
+        syn::Expr::Cast(cast) => is_const_len(&cast.expr),
+        _ => false,
+    }
+}
+
 fn make_check_secret_block_safe(e: proc_macro2::TokenStream, do_check: bool) -> proc_macro2::TokenStream {
     if do_check {
         // TODO: The outer { } are needed or there's an error in millionaires
@@ -1056,9 +1695,10 @@ fn make_check_secret_block_safe(e: proc_macro2::TokenStream, do_check: bool) ->
     }
 }
 
-fn make_check_secret_block_safe_ptr_read(e: proc_macro2::TokenStream, do_check: bool) -> proc_macro2::TokenStream {
+// The check is spanned at the path so that a non-InvisibleSideEffectFree capture is reported where it's used.
+fn make_check_secret_block_safe_ptr_read(e: proc_macro2::TokenStream, span: proc_macro2::Span, do_check: bool) -> proc_macro2::TokenStream {
     if do_check {
-        quote::quote! {
+        quote::quote_spanned! {span=>
             { let tmp = &(#e); unsafe { ::secret_structs::secret::check_ISEF_unsafe(tmp) } }
         }
     } else {
@@ -1122,6 +1762,12 @@ fn check_block(input: &syn::Block, secrecy_label: &Option<syn::Type>) -> TokenSt
                     match item {
                         // Const items can never have side-effects, so leave them alone.
                         syn::Item::Const(const_item) => const_item.into_token_stream(),
+                        // Definitions have no runtime effect. Calling a nested fn still goes through
+                        // the usual call checks (it must be allowlisted or return Vetted), and reading
+                        // a static is checked like any other path.
+                        syn::Item::Fn(fn_item) => fn_item.into_token_stream(),
+                        syn::Item::Static(static_item) => static_item.into_token_stream(),
+                        syn::Item::Use(use_item) => use_item.into_token_stream(),
                         _ => {
                             let i = item.into_token_stream();
                             make_check_secret_block_safe(i, true)
@@ -1129,6 +1775,12 @@ fn check_block(input: &syn::Block, secrecy_label: &Option<syn::Type>) -> TokenSt
                     }
                 }
                 syn::Stmt::Expr(expr) => check_expr(expr, secrecy_label, true),
+                syn::Stmt::Semi(expr, _) if LetElse::from_verbatim(expr).is_some() => {
+                    let let_else = LetElse::from_verbatim(expr).unwrap();
+                    let init = check_expr(&let_else.init, secrecy_label, true);
+                    let diverge: proc_macro2::TokenStream = check_block(&let_else.diverge, secrecy_label).into();
+                    let_else.to_tokens_with(init, diverge)
+                }
                 syn::Stmt::Semi(expr, _) => {
                     let expr_tokens = check_expr(expr, secrecy_label, true);
                     quote::quote! {
@@ -1171,6 +1823,12 @@ fn expand_block(input: &syn::Block, secrecy_label: &Option<syn::Type>) -> TokenS
                     item.into_token_stream().into()
                 }
                 syn::Stmt::Expr(expr) => expand_expr(expr, secrecy_label),
+                syn::Stmt::Semi(expr, _) if LetElse::from_verbatim(expr).is_some() => {
+                    let let_else = LetElse::from_verbatim(expr).unwrap();
+                    let init = expand_expr(&let_else.init, secrecy_label);
+                    let diverge: proc_macro2::TokenStream = expand_block(&let_else.diverge, secrecy_label).into();
+                    let_else.to_tokens_with(init, diverge)
+                }
                 syn::Stmt::Semi(expr, _) => {
                     let expr_tokens = expand_expr(expr, secrecy_label);
                     quote::quote! {
@@ -1210,9 +1868,60 @@ fn get_trampoline_fn_name(fn_name: &str, special: &str) -> String {
     "__".to_owned() + fn_name + "_secret_trampoline"  + special
 }
 
+/** Marks a function as side-effect free so secret blocks may call it. Also applies to a whole
+inherent impl block, which is needed for associated functions without a receiver: their
+trampolines become associated functions too, so the wrapper must call them through Self. */
 #[proc_macro_attribute]
 pub fn side_effect_free_attr(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let fn_definition: syn::ItemFn = syn::parse(item).unwrap();
+    match syn::parse::<syn::Item>(item).unwrap() {
+        syn::Item::Fn(fn_definition) => side_effect_free_fn(fn_definition, false).into(),
+        syn::Item::Impl(item_impl) if item_impl.trait_.is_some() => quote::quote_spanned! {item_impl.span()=>
+            compile_error!("side_effect_free_attr can't be applied to a trait impl, since it changes each method's signature");
+        }.into(),
+        syn::Item::Impl(mut item_impl) => {
+            item_impl.items = item_impl.items.into_iter().map(|item| match item {
+                syn::ImplItem::Method(method) => syn::ImplItem::Verbatim(side_effect_free_fn(
+                    syn::ItemFn { attrs: method.attrs, vis: method.vis, sig: method.sig, block: Box::new(method.block) },
+                    true,
+                )),
+                other => other,
+            }).collect();
+            item_impl.into_token_stream().into()
+        }
+        other => quote::quote_spanned! {other.span()=>
+            compile_error!("side_effect_free_attr only applies to functions and inherent impl blocks");
+        }.into(),
+    }
+}
+
+// Values of a generic type parameter end up in Vetted and in the checking code, which both need
+// InvisibleSideEffectFree, so require it of every type parameter rather than making users spell it.
+fn add_isef_bounds(generics: &mut syn::Generics) {
+    let type_params: Vec<Ident> = generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for ident in type_params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { #ident: ::secret_structs::secret::InvisibleSideEffectFree });
+    }
+}
+
+/** Adds an InvisibleSideEffectFree bound to each `impl Trait` in a signature, the anonymous
+counterpart of add_isef_bounds. A returned `impl Iterator` then still satisfies Vetted's bound. */
+struct IsefImplTraits;
+
+impl VisitMut for IsefImplTraits {
+    fn visit_type_impl_trait_mut(&mut self, ty: &mut syn::TypeImplTrait) {
+        syn::visit_mut::visit_type_impl_trait_mut(self, ty);
+        ty.bounds.push(syn::parse_quote! { ::secret_structs::secret::InvisibleSideEffectFree });
+    }
+
+    fn visit_block_mut(&mut self, _block: &mut syn::Block) {}
+}
+
+fn side_effect_free_fn(mut fn_definition: syn::ItemFn, in_impl: bool) -> proc_macro2::TokenStream {
+    add_isef_bounds(&mut fn_definition.sig.generics);
+    IsefImplTraits.visit_signature_mut(&mut fn_definition.sig);
     let new_fn_name_checked = get_trampoline_fn_name(&fn_definition.sig.ident.to_string(), &"_checked".to_string());
     let new_fn_name_unchecked = get_trampoline_fn_name(&fn_definition.sig.ident.to_string(), &"_unchecked".to_string());
 
@@ -1274,6 +1983,16 @@ pub fn side_effect_free_attr(_attr: TokenStream, item: TokenStream) -> TokenStre
         _ => quote::quote! {}
     };
 
+    // The wrapper only forwards to the unchecked trampoline, so both get the same inline hint: the
+    // user's, if the function has one, and otherwise #[inline(always)] so that hot kernels inline.
+    let inline_attr = match fn_definition.attrs.iter().find(|attr| attr.path.is_ident("inline")) {
+        Some(attr) => quote! { #attr },
+        None => {
+            fn_definition.attrs.push(syn::parse_quote! { #[inline(always)] });
+            quote! { #[inline(always)] }
+        }
+    };
+
     let mut new_fn_definition_unchecked = fn_definition.clone();
     let new_fn_name_unchecked = Ident::new(&new_fn_name_unchecked, fn_definition.span());
     new_fn_definition_unchecked.sig.ident = new_fn_name_unchecked.clone();
@@ -1287,6 +2006,8 @@ pub fn side_effect_free_attr(_attr: TokenStream, item: TokenStream) -> TokenStre
 
     let self_block = if is_method {
         quote! {self.}
+    } else if in_impl {
+        quote! {Self::}
     } else {
         quote! {}
     };
@@ -1296,34 +2017,98 @@ pub fn side_effect_free_attr(_attr: TokenStream, item: TokenStream) -> TokenStre
         
         #new_fn_definition_checked
 
-        #[inline(always)]
+        #inline_attr
         #fn_access #fn_const unsafe fn #fn_name<#generic_params>(#fn_args) -> ::secret_structs::secret::Vetted<#fn_return_type> #where_clause {
             //if true {
-                ::secret_structs::secret::Vetted::<#fn_return_type>::wrap(#self_block#new_fn_name_unchecked(#param_names))
+                // No turbofish, since the return type may be an `impl Trait`
+                ::secret_structs::secret::Vetted::wrap(#self_block#new_fn_name_unchecked(#param_names))
             //} else {
             //    ::secret_structs::secret::Vetted::<#fn_return_type>::wrap(#self_block#new_fn_name_checked(#param_names))
             //}
         }
     };
 
-    gen.into()
+    gen
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p.qself.is_none() && p.path.segments.last().map_or(false, |s| s.ident == "PhantomData"),
+        _ => false,
+    }
+}
+
+fn field_label(index: usize, field: &syn::Field) -> String {
+    match &field.ident {
+        Some(ident) => ident.to_string(),
+        None => index.to_string(),
+    }
+}
+
+fn isef_transparent(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut transparent = false;
+    for attr in attrs.iter().filter(|a| a.path.is_ident("isef")) {
+        if let syn::Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested.iter() {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(p)) if p.is_ident("transparent") => transparent = true,
+                    _ => return Err(syn::Error::new(nested.span(), "unknown isef option; expected #[isef(transparent)]")),
+                }
+            }
+        }
+    }
+    Ok(transparent)
 }
 
 // Based on https://blog.turbo.fish/proc-macro-simple-derive/
-#[proc_macro_derive(InvisibleSideEffectFreeDerive)]
+/** With #[isef(transparent)] on a single-field tuple struct, also derives inner()/inner_mut(),
+which return the wrapped value as Vetted so secret blocks can call them like side_effect_free_attr
+methods. The wrapped type must itself be InvisibleSideEffectFree, as for any derived field. */
+#[proc_macro_derive(InvisibleSideEffectFreeDerive, attributes(isef))]
 pub fn secret_block_safe_macro(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
 
-    let fields = match input.data {
-        Data::Struct(DataStruct {
-            fields: Fields::Named(fields),
-            ..
-        }) => fields.named,
-        _ => panic!("this derive macro only works on structs with named fields"),
+    let st_name = input.ident;
+
+    let transparent = match isef_transparent(&input.attrs) {
+        Ok(transparent) => transparent,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let transparent_inner = match &input.data {
+        _ if !transparent => None,
+        Data::Struct(DataStruct { fields: Fields::Unnamed(fields), .. }) if fields.unnamed.len() == 1 => {
+            Some((fields.unnamed[0].vis.clone(), fields.unnamed[0].ty.clone()))
+        }
+        _ => {
+            return syn::Error::new(st_name.span(), "#[isef(transparent)] only applies to tuple structs with a single field")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    // Each field is paired with a name for diagnostics: "f" for a named field, "0" for a
+    // positional one, prefixed by the variant name for enums.
+    let fields: Vec<(String, syn::Field)> = match input.data {
+        Data::Struct(DataStruct { fields, .. }) => fields
+            .into_iter()
+            .enumerate()
+            .map(|(i, f)| (field_label(i, &f), f))
+            .collect(),
+        Data::Enum(data) => data
+            .variants
+            .into_iter()
+            .flat_map(|v| {
+                let variant = v.ident.to_string();
+                v.fields
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(i, f)| (format!("{}_{}", variant, field_label(i, &f)), f))
+            })
+            .collect(),
+        Data::Union(_) => panic!("this derive macro only works on structs and enums"),
     };
 
-    let st_name = input.ident;
     let st_generics = input.generics;
     let st_generics_params = st_generics.clone().params;
     let st_generics_names = st_generics_params
@@ -1337,14 +2122,16 @@ pub fn secret_block_safe_macro(input: TokenStream) -> TokenStream {
     };
     let st_where_clause = st_generics.clone().where_clause;
 
-    let getters = fields.into_iter().map(|f| {
-        // Interpolation only works for variables, not arbitrary expressions.
-        // That's why we need to move these fields into local variables first
-        // (borrowing would also work though).
-        let _field_name = f.ident.unwrap();
+    // A dedicated checker per field, named after the field and spanned to its type, so that a
+    // non-ISEF field is reported as, e.g., "required by a bound in `check_field_cache_of_Holder`".
+    // PhantomData fields (e.g., a label parameter) hold no data, so there's nothing to check
+    let getters = fields.into_iter().filter(|(_, f)| !is_phantom_data(&f.ty)).map(|(field_name, f)| {
         let field_ty = f.ty;
-        quote! {
-            ::secret_structs::secret::check_type_is_secret_block_safe::<#field_ty>();
+        let checker = quote::format_ident!("check_field_{}_of_{}", field_name, st_name);
+        quote::quote_spanned! {field_ty.span()=>
+            #[allow(non_snake_case)]
+            fn #checker<T: ::secret_structs::secret::InvisibleSideEffectFree>() {}
+            #checker::<#field_ty>();
         }
     });
 
@@ -1363,7 +2150,188 @@ pub fn secret_block_safe_macro(input: TokenStream) -> TokenStream {
         #[automatically_derived]
         impl #st_generics !::std::ops::DerefMut for #st_name #st_generics_names #st_where_clause {}
     };
+    let accessors = match transparent_inner {
+        Some((vis, inner_ty)) => quote! {
+            #[automatically_derived]
+            impl #st_generics #st_name #st_generics_names #st_where_clause {
+                #[inline(always)]
+                #vis unsafe fn inner(&self) -> ::secret_structs::secret::Vetted<&#inner_ty> {
+                    ::secret_structs::secret::Vetted::wrap(&self.0)
+                }
+                #[inline(always)]
+                #vis unsafe fn inner_mut(&mut self) -> ::secret_structs::secret::Vetted<&mut #inner_ty> {
+                    ::secret_structs::secret::Vetted::wrap(&mut self.0)
+                }
+            }
+        },
+        None => quote! {},
+    };
+    let expanded = quote! { #expanded #accessors };
 
     // Hand the output tokens back to the compiler
     TokenStream::from(expanded)
 }
+
+/** Derives `<field>_secret(&self)` getters that return a clone of each field wrapped as a
+Secret at the label given by `#[classify(label = "...")]`. A bare label name such as
+"Label_A" refers to `secret_structs::lattice`; otherwise the string is parsed as a type path. */
+#[proc_macro_derive(Classify, attributes(classify))]
+pub fn classify_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let label = match classify_label(&input.attrs) {
+        Ok(label) => label,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let fields = match input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields.named,
+        _ => panic!("this derive macro only works on structs with named fields"),
+    };
+
+    let st_name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let getters = fields.into_iter().map(|f| {
+        let field_name = f.ident.unwrap();
+        let field_ty = f.ty;
+        let vis = f.vis;
+        let getter_name = quote::format_ident!("{}_secret", field_name);
+        quote! {
+            #vis fn #getter_name(&self) -> ::secret_structs::secret::Secret<#field_ty, #label> {
+                unsafe { ::secret_structs::secret::Secret::<#field_ty, #label>::new(::std::clone::Clone::clone(&self.#field_name)) }
+            }
+        }
+    });
+
+    let expanded: proc_macro2::TokenStream = quote! {
+        #[automatically_derived]
+        impl #impl_generics #st_name #ty_generics #where_clause {
+            #(#getters)*
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+fn classify_label(attrs: &[syn::Attribute]) -> syn::Result<Type> {
+    for attr in attrs.iter().filter(|a| a.path.is_ident("classify")) {
+        if let syn::Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested.iter() {
+                if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = nested {
+                    if let (true, syn::Lit::Str(s)) = (nv.path.is_ident("label"), &nv.lit) {
+                        let label: Type = s.parse()?;
+                        return Ok(match &label {
+                            Type::Path(p) if p.qself.is_none() && p.path.get_ident().is_some() => {
+                                syn::parse_quote! { ::secret_structs::lattice::#label }
+                            }
+                            _ => label,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Err(syn::Error::new(
+        proc_macro2::Span::call_site(),
+        "Classify requires #[classify(label = \"...\")]",
+    ))
+}
+
+/** Derives `Label` for a user-defined marker label, plus one `MoreSecretThan` edge per type listed in
+`#[more_secret_than(...)]`. Reflexivity comes from the blanket impl in `lattice`; transitive edges
+are not inferred, so list every label the new one is more secret than. Label_Top is made more
+secret than the new label. The struct must also derive Default. */
+#[proc_macro_derive(Label, attributes(more_secret_than))]
+pub fn label_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let lower_labels = match more_secret_than_labels(&input.attrs) {
+        Ok(labels) => labels,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let st_name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let edges = lower_labels.iter().map(|lower| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::secret_structs::lattice::MoreSecretThan<#lower> for #st_name #ty_generics #where_clause {}
+        }
+    });
+
+    let expanded: proc_macro2::TokenStream = quote! {
+        #[automatically_derived]
+        unsafe impl #impl_generics ::secret_structs::lattice::Label for #st_name #ty_generics #where_clause {}
+        #[automatically_derived]
+        impl #impl_generics ::secret_structs::lattice::MoreSecretThan<#st_name #ty_generics> for ::secret_structs::lattice::Label_Top #where_clause {}
+        #(#edges)*
+    };
+
+    TokenStream::from(expanded)
+}
+
+fn more_secret_than_labels(attrs: &[syn::Attribute]) -> syn::Result<Vec<syn::Path>> {
+    let mut labels = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("more_secret_than")) {
+        let list = attr.parse_args_with(syn::punctuated::Punctuated::<syn::Path, Comma>::parse_terminated)?;
+        labels.extend(list);
+    }
+    Ok(labels)
+}
+
+/** Arguments of `#[secret_test(leak = { ... }, error = "...")]`. */
+struct SecretTestArgs {
+    leak: Block,
+}
+
+impl Parse for SecretTestArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut leak = None;
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            if key == "leak" {
+                leak = Some(input.parse::<Block>()?);
+            } else if key == "error" {
+                // Only read by tests/autotest.sh, which checks the compiler output for it
+                input.parse::<syn::LitStr>()?;
+            } else {
+                return Err(syn::Error::new(key.span(), "secret_macros: secret_test takes `leak = { ... }` and `error = \"...\"`"));
+            }
+            if !input.is_empty() {
+                input.parse::<Comma>()?;
+            }
+        }
+        match leak {
+            Some(leak) => Ok(SecretTestArgs { leak }),
+            None => Err(input.error("secret_macros: secret_test needs a `leak = { ... }` block that shouldn't compile")),
+        }
+    }
+}
+
+/** Pairs a functional test with a leak attempt that must not compile. The function is kept as is
+(and is a `#[test]` under `--test`). The `leak` block becomes a function `<name>_leak` that only
+exists with `--cfg secret_test_leak="<name>"`, so each leak attempt is compiled on its own;
+tests/autotest.sh does that and expects the build to fail with the `error` text. */
+#[proc_macro_attribute]
+pub fn secret_test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as SecretTestArgs);
+    let test_fn = parse_macro_input!(item as syn::ItemFn);
+    let name = test_fn.sig.ident.to_string();
+    let leak_fn = Ident::new(&format!("{}_leak", name), test_fn.sig.ident.span());
+    let leak = args.leak;
+    let expanded: proc_macro2::TokenStream = quote! {
+        #[cfg_attr(test, test)]
+        #test_fn
+
+        #[cfg(secret_test_leak = #name)]
+        #[allow(dead_code, unused_variables)]
+        fn #leak_fn() #leak
+    };
+    expanded.into()
+}
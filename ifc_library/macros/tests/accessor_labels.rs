@@ -0,0 +1,19 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  // Secret labels: declassify and declassify_ref.
+  let secret: st::Secret<i32, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB { wrap_secret(5) });
+  assert_eq!(*secret.declassify_ref(), 5);
+
+  // Partway down, then all the way.
+  let lowered: st::Secret<i32, lat::Label_A> = secret.declassify_to::<lat::Label_A>();
+  let public: st::Secret<i32, lat::Label_Empty> = lowered.declassify();
+
+  // Public label: get_value_ref and get_value_consume.
+  assert_eq!(*public.get_value_ref(), 5);
+  assert_eq!(public.get_value_consume(), 5);
+}
@@ -0,0 +1,20 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  // One bitboard per player; only squares both (or either) occupy survive.
+  let ships_a: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(0b1100u64) });
+  let shots_b: st::Secret<u64, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(0b1010u64) });
+  let hits: st::Secret<u64, lat::Label_AB> = ships_a.clone().and(shots_b.clone());
+  assert_eq!(hits.declassify().get_value_consume(), 0b1000);
+
+  let covered: st::Secret<u64, lat::Label_AB> = ships_a.or(shots_b);
+  assert_eq!(covered.declassify().get_value_consume(), 0b1110);
+
+  // `|` is also allowed inside secret blocks now.
+  let mask: st::Secret<u8, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(0b01u8 | 0b10u8) });
+  assert_eq!(mask.declassify().get_value_consume(), 0b11);
+}
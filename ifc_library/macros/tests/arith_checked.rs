@@ -0,0 +1,39 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Also built with -O by arith_checked_release.rs, so the results must not depend on the profile.
+pub fn main() {
+  let big: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(i32::MAX) });
+  let small: st::Secret<u8, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3) });
+
+  // In range: same result as unchecked arithmetic
+  let ok: st::Secret<(i32, u8), lat::Label_A> = secret_structs::secret_block!(lat::Label_A #[arith = "checked"] {
+    let mut s: u8 = *unwrap_secret_ref(&small);
+    s *= 4;
+    s -= 2;
+    wrap_secret((*unwrap_secret_ref(&big) - 7 / 2, s))
+  });
+  assert_eq!(ok.declassify().get_value_consume(), (i32::MAX - 3, 10));
+
+  // Overflow always panics, so the block returns its default value
+  let overflowed: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A #[arith = "checked"] {
+    wrap_secret(*unwrap_secret_ref(&big) + 1)
+  });
+  assert_eq!(overflowed.declassify().get_value_consume(), 0);
+
+  let overflowed_assign: st::Secret<u8, lat::Label_A> = secret_structs::secret_block!(lat::Label_A #[arith = "checked"] {
+    let mut s: u8 = *unwrap_secret_ref(&small);
+    s -= 4;
+    wrap_secret(s)
+  });
+  assert_eq!(overflowed_assign.declassify().get_value_consume(), 0);
+
+  // Including inside vec![...]
+  let overflowed_vec: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A #[arith = "checked"] {
+    wrap_secret(vec![1, *unwrap_secret_ref(&big) + 1])
+  });
+  assert_eq!(overflowed_vec.declassify().get_value_consume(), vec![]);
+}
@@ -0,0 +1,2 @@
+// compile-flags: -O
+include!("arith_checked.rs");
@@ -0,0 +1,23 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let boxed: st::Secret<Box<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(std::boxed::Box::new(42))
+  });
+  let inner: st::Secret<&i32, lat::Label_A> = boxed.as_deref();
+
+  let values: Vec<i32> = vec![1, 2, 3];
+  let vec: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(values) });
+  let slice: st::Secret<&[i32], lat::Label_A> = vec.as_slice();
+
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let x: &i32 = *unwrap_secret_ref(&inner);
+    let s: &[i32] = *unwrap_secret_ref(&slice);
+    wrap_secret(*x + s[2])
+  });
+  assert_eq!(result.declassify().get_value_consume(), 45);
+}
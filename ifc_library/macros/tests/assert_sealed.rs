@@ -0,0 +1,25 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::InvisibleSideEffectFreeDerive;
+use secret_structs::assert_sealed;
+use secret_structs::secret as st;
+
+#[derive(Clone, Copy, InvisibleSideEffectFreeDerive)]
+struct Derived {
+  v: u64,
+}
+
+// A hand-written impl, which is what assert_sealed! is for
+#[derive(Clone, Copy)]
+struct Handwritten(&'static str);
+unsafe impl st::InvisibleSideEffectFree for Handwritten {}
+
+assert_sealed!(i32, (u8, bool), [f64; 4], Derived, Handwritten);
+
+pub fn main() {
+  let d = Derived { v: 1 };
+  let h = Handwritten("h");
+  assert_eq!((d.v, h.0), (1, "h"));
+}
@@ -0,0 +1,27 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Built by audit_calls.sh with the audit_calls feature, which checks that each call below is listed.
+
+#[side_effect_free_attr]
+pub fn double(x: i32) -> i32 {
+  x * 2
+}
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3) });
+  let b: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let a = unwrap_secret(a);
+    wrap_secret(std::cmp::max(double(a), 4))
+  });
+  let v: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut v = std::vec::Vec::new();
+    std::vec::Vec::push(&mut v, unwrap_secret(b));
+    wrap_secret(v)
+  });
+  assert_eq!(v.declassify().get_value_consume(), vec![6]);
+}
@@ -0,0 +1,73 @@
+#!/bin/bash
+
+# Builds audit_calls.rs with the audit_calls feature and checks the list of trusted calls it records.
+# Please run shellcheck if you edit this file.
+
+set -eou pipefail
+
+TESTS_DIR=$(cd "$(dirname "$0")" && pwd)
+readonly TESTS_DIR
+
+STRUCTS_DIR="${TESTS_DIR}/../../secret_structs"
+readonly STRUCTS_DIR
+
+# A separate target directory, so the feature doesn't leak into the regular test build
+AUDIT_TARGET_DIR="${TESTS_DIR}/../target/audit_calls"
+readonly AUDIT_TARGET_DIR
+
+LIBRARY_EXT=".so"
+if [[ "$(uname)" = "Darwin" ]]; then
+  LIBRARY_EXT=".dylib"
+fi
+readonly LIBRARY_EXT
+
+# Take the macros library's path from cargo, since the target directory may hold older builds
+pushd "${STRUCTS_DIR}" >/dev/null 2>/dev/null
+macros_lib=$(cargo build --release --lib --features audit_calls --target-dir "${AUDIT_TARGET_DIR}" \
+  --message-format=json 2>/dev/null | grep -o "[^\"]*/libsecret_macros-[^\"]*${LIBRARY_EXT}" | head -n 1)
+popd >/dev/null 2>/dev/null
+
+WORK_DIR=$(mktemp -d)
+readonly WORK_DIR
+trap 'rm -rf "${WORK_DIR}"' EXIT
+
+AUDIT_FILE="${WORK_DIR}/calls.tsv"
+readonly AUDIT_FILE
+
+pushd "${TESTS_DIR}" >/dev/null 2>/dev/null
+set +e
+compile_output=$(SECRET_MACROS_AUDIT_FILE="${AUDIT_FILE}" rustc --extern secret_macros="${macros_lib}" \
+  --extern secret_structs="${AUDIT_TARGET_DIR}/release/libsecret_structs.rlib" \
+  -L dependency="${AUDIT_TARGET_DIR}/release/deps" \
+  audit_calls.rs -o "${WORK_DIR}/audit_calls" 2>&1)
+exit_code=$?
+set -e
+popd >/dev/null 2>/dev/null
+
+if [[ "${exit_code}" -ne 0 ]]; then
+  echo "TEST FAILED: audit_calls.sh"
+  echo "Output:"
+  echo "${compile_output}"
+  exit 1
+fi
+
+EXIT_STATUS=0
+for expected in \
+  $'vetted\tdouble\taudit_calls.rs:19:' \
+  $'allowlisted\tstd::cmp::max\taudit_calls.rs:19:' \
+  $'allowlisted\tstd::vec::Vec::new\taudit_calls.rs:22:' \
+  $'allowlisted\tstd::vec::Vec::push\taudit_calls.rs:23:'; do
+  if ! grep -qF "${expected}" "${AUDIT_FILE}"; then
+    echo "TEST FAILED: audit_calls.sh"
+    echo "Missing entry: ${expected}"
+    EXIT_STATUS=1
+  fi
+done
+
+if [[ "${EXIT_STATUS}" -eq 0 ]]; then
+  echo "TEST PASSED: audit_calls.sh"
+else
+  echo "Recorded calls:"
+  cat "${AUDIT_FILE}"
+fi
+exit "${EXIT_STATUS}"
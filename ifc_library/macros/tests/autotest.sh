@@ -84,6 +84,20 @@ function compile_should_fail() {
   [[ "${rust_src_filename}" =~ "no_compile".* ]] || [[ "${rust_src_filename}" =~ "not_yet_supported".* ]]
 }
 
+# Prints the expected compiler error text given by a `// error-pattern: ...` line, if any.
+function expected_error_pattern() {
+  local rust_src_filename
+  rust_src_filename="$1"
+  sed -n 's|^// error-pattern: \(.*\)$|\1|p' "${rust_src_filename}" | head -n 1
+}
+
+# Prints the extra rustc flags given by a `// compile-flags: ...` line, if any.
+function extra_compile_flags() {
+  local rust_src_filename
+  rust_src_filename="$1"
+  sed -n 's|^// compile-flags: \(.*\)$|\1|p' "${rust_src_filename}" | head -n 1
+}
+
 function build_test_files() {
   pushd "${MACROS_TESTS_DIR}" >/dev/null 2>/dev/null
 
@@ -94,9 +108,10 @@ function build_test_files() {
     outputname=$(rust_src_filename_to_test_filename "${filename}")
 
     set +e
-    local compile_output exit_code
+    local compile_output exit_code compile_flags
+    read -r -a compile_flags <<< "$(extra_compile_flags "${filename}")"
     #compile_output=$(rustc --extern secret_macros=${MACROS_RELEASE_DIR}/libsecret_macros${LIBRARY_EXT} -L ${STRUCTS_RELEASE_DIR} -L ${STRUCTS_RELEASE_DIR}/deps "${filename}" -o "${outputname}" 2>&1)
-    compile_output=$(rustc --extern secret_macros=${MACROS_RELEASE_DIR}/libsecret_macros${LIBRARY_EXT} -L ${STRUCTS_RELEASE_DIR} "${filename}" -o "${outputname}" 2>&1)
+    compile_output=$(rustc --extern secret_macros=${MACROS_RELEASE_DIR}/libsecret_macros${LIBRARY_EXT} -L ${STRUCTS_RELEASE_DIR} "${compile_flags[@]}" "${filename}" -o "${outputname}" 2>&1)
     exit_code=$?
     set -e
 
@@ -112,7 +127,18 @@ function build_test_files() {
       iecho
       EXIT_STATUS=1
     elif compile_should_fail "${filename}"; then
-      iecho "TEST PASSED: ${filename} did NOT compile, as expected."
+      local error_pattern
+      error_pattern=$(expected_error_pattern "${filename}")
+      if [[ -n "${error_pattern}" ]] && [[ "${compile_output}" != *"${error_pattern}"* ]]; then
+        iecho "TEST FAILED: ${filename}"
+        iecho "The file ${filename} did not compile, but the error did not contain: ${error_pattern}"
+        iecho "Output:"
+        iecho "${compile_output}"
+        iecho
+        EXIT_STATUS=1
+      else
+        iecho "TEST PASSED: ${filename} did NOT compile, as expected."
+      fi
     fi
 
   done
@@ -121,6 +147,59 @@ function build_test_files() {
   popd >/dev/null 2>/dev/null
 }
 
+# Prints `name<TAB>error` for each function annotated with #[secret_test(...)], where error is the
+# text given by its `error = "..."` argument, if any.
+function secret_test_cases() {
+  local rust_src_filename
+  rust_src_filename="$1"
+  awk '
+    /#\[secret_test/ { inside = 1; pattern = "" }
+    inside && /error = "/ { pattern = $0; sub(/.*error = "/, "", pattern); sub(/",?[[:space:]]*$/, "", pattern) }
+    inside && /^[[:space:]]*(pub )?fn / {
+      name = $0; sub(/^[[:space:]]*(pub )?fn /, "", name); sub(/[^A-Za-z0-9_].*/, "", name)
+      print name "\t" pattern; inside = 0
+    }
+  ' "${rust_src_filename}"
+}
+
+# Compiles the leak attempt of each #[secret_test] on its own and checks that it does not compile.
+function build_secret_test_leaks() {
+  pushd "${MACROS_TESTS_DIR}" >/dev/null 2>/dev/null
+
+  shopt -s nullglob
+  for filename in *.rs; do
+    local name error_pattern
+    while IFS=$'\t' read -r name error_pattern; do
+      iecho "Building the leak attempt of ${name} in ${filename}..."
+      set +e
+      local compile_output exit_code
+      compile_output=$(rustc --extern secret_macros=${MACROS_RELEASE_DIR}/libsecret_macros${LIBRARY_EXT} -L ${STRUCTS_RELEASE_DIR} --cfg "secret_test_leak=\"${name}\"" --emit=metadata -o "${name}_leak.rmeta" "${filename}" 2>&1)
+      exit_code=$?
+      set -e
+      rm -f "${name}_leak.rmeta"
+
+      if [[ "${exit_code}" -eq 0 ]]; then
+        iecho "TEST FAILED: ${filename} (${name})"
+        iecho "The leak attempt of ${name} compiled, but a compilation error is expected."
+        iecho
+        EXIT_STATUS=1
+      elif [[ -n "${error_pattern}" ]] && [[ "${compile_output}" != *"${error_pattern}"* ]]; then
+        iecho "TEST FAILED: ${filename} (${name})"
+        iecho "The leak attempt of ${name} did not compile, but the error did not contain: ${error_pattern}"
+        iecho "Output:"
+        iecho "${compile_output}"
+        iecho
+        EXIT_STATUS=1
+      else
+        iecho "TEST PASSED: the leak attempt of ${name} did NOT compile, as expected."
+      fi
+    done < <(secret_test_cases "${filename}")
+  done
+  shopt -u nullglob
+
+  popd >/dev/null 2>/dev/null
+}
+
 function run_tests() {
   pushd "${MACROS_TESTS_DIR}" >/dev/null 2>/dev/null
 
@@ -180,9 +259,23 @@ iecho "Building test files..."
 with_indent \
   build_test_files
 
+iecho "Building #[secret_test] leak attempts..."
+with_indent \
+  build_secret_test_leaks
+
 iecho "Running tests..."
 with_indent \
   run_tests
 
+iecho "Checking the audit_calls feature..."
+if ! "${MACROS_TESTS_DIR}/audit_calls.sh"; then
+  EXIT_STATUS=1
+fi
+
+iecho "Checking that side_effect_free_attr kernels inline..."
+if ! "${MACROS_TESTS_DIR}/inline_trampolines.sh"; then
+  EXIT_STATUS=1
+fi
+
 rm_test_binaries
 exit "${EXIT_STATUS}"
\ No newline at end of file
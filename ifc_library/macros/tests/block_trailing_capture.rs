@@ -0,0 +1,20 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let offset = 4i32;
+  // Inner blocks may end in a captured ISEF value such as `offset`.
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let y = {
+      let z = 1i32;
+      z;
+      offset
+    };
+    let w = if y > 0i32 { offset } else { 0i32 };
+    wrap_secret(y + w)
+  });
+  assert_eq!(result.declassify().get_value_consume(), 8);
+}
@@ -0,0 +1,21 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+pub fn increment(x: &mut i32) {
+  *x += 1;
+}
+
+pub fn main() {
+  let mut counter: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(41) });
+  {
+    let mut guard: st::SecretGuard<i32, lat::Label_A> = counter.borrow_secret();
+    guard.map(|x| unsafe { increment(x) });
+  }
+  counter.borrow_secret().map(|x| unsafe { increment(x) }).map(|x| unsafe { increment(x) });
+  assert_eq!(counter.declassify().get_value_consume(), 44);
+}
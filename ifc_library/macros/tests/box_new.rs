@@ -0,0 +1,14 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let x: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(6) });
+  let boxed: st::Secret<Box<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let b: Box<i32> = std::boxed::Box::new(*unwrap_secret_ref(&x) + 1);
+    wrap_secret(b)
+  });
+  assert_eq!(*boxed.declassify().get_value_consume(), 7);
+}
@@ -0,0 +1,16 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(42) });
+  let b: st::Secret<i32, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(84) });
+  let a: st::Secret<i32, lat::Label_AB> = a.cast_label();
+  let b: st::Secret<i32, lat::Label_AB> = b.cast_label::<lat::Label_AB>();
+  let result: st::Secret<i32, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB {
+    wrap_secret(*unwrap_secret_ref(&a) + *unwrap_secret_ref(&b))
+  });
+  assert_eq!(result.declassify().get_value_consume(), 126);
+}
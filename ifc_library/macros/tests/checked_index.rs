@@ -0,0 +1,22 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let v: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut v = std::vec::Vec::new();
+    std::vec::Vec::push(&mut v, 10i32);
+    std::vec::Vec::push(&mut v, 20i32);
+    wrap_secret(v)
+  });
+  let hit: st::Secret<usize, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(1usize) });
+  let miss: st::Secret<usize, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(7usize) });
+
+  // Whether the index hit stays secret at the join of both labels.
+  let found: st::Secret<Option<&i32>, lat::Label_AB> = v.checked_index(hit);
+  let missing: st::Secret<Option<&i32>, lat::Label_AB> = v.checked_index(miss);
+  assert_eq!(found.declassify().get_value_consume(), Some(&20));
+  assert_eq!(missing.declassify().get_value_consume(), None);
+}
@@ -0,0 +1,22 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+fn clamp_abc(x: i32, lo: i32, hi: i32) -> i32 {
+  let x: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(x) });
+  let lo: st::Secret<i32, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(lo) });
+  let hi: st::Secret<i32, lat::Label_C> = secret_structs::secret_block!(lat::Label_C { wrap_secret(hi) });
+  let clamped: st::Secret<i32, lat::Label_ABC> = x.clamp(lo, hi);
+  clamped.declassify().get_value_consume()
+}
+
+pub fn main() {
+  assert_eq!(clamp_abc(5, 0, 10), 5);
+  assert_eq!(clamp_abc(-3, 0, 10), 0);
+  assert_eq!(clamp_abc(42, 0, 10), 10);
+  assert_eq!(clamp_abc(i32::MIN, -1, 1), -1);
+  // An empty range can't panic without leaking, so the upper bound wins.
+  assert_eq!(clamp_abc(5, 10, 0), 0);
+}
@@ -0,0 +1,31 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+use secret_structs::secret::Classifiable;
+
+// Label-generic code classifies its public inputs at whatever label the caller picks.
+fn classify_all<T, L>(items: Vec<T>) -> Vec<st::Secret<T, L>>
+where
+  T: Classifiable<L>,
+  L: lat::Label,
+{
+  items.into_iter().map(|item| item.classify()).collect()
+}
+
+pub fn main() {
+  let secrets = classify_all::<u32, lat::Label_A>(vec![1, 2, 3]);
+  let total: st::Secret<u32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut total = 0u32;
+    for s in secrets.iter() {
+      total += *unwrap_secret_ref(s);
+    }
+    wrap_secret(total)
+  });
+  assert_eq!(total.declassify().get_value_consume(), 6);
+
+  // Outside generic code, the label can be named on the trait.
+  let one: st::Secret<&str, lat::Label_B> = Classifiable::<lat::Label_B>::classify("one");
+  assert_eq!(one.declassify().get_value_consume(), "one");
+}
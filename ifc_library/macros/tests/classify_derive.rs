@@ -0,0 +1,25 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::Classify;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(Classify)]
+#[classify(label = "Label_A")]
+pub struct User {
+  pub ssn: u64,
+  pub name: String,
+}
+
+pub fn main() {
+  let user = User { ssn: 123456789, name: String::from("alice") };
+  let ssn: st::Secret<u64, lat::Label_A> = user.ssn_secret();
+  let name: st::Secret<String, lat::Label_A> = user.name_secret();
+  let result: st::Secret<(u64, usize), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let s: &u64 = unwrap_secret_ref(&ssn);
+    let n: &String = unwrap_secret_ref(&name);
+    wrap_secret((*s + 1, std::string::String::len(n)))
+  });
+  assert_eq!(result.declassify().get_value_consume(), (123456790, 5));
+}
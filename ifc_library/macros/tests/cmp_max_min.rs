@@ -0,0 +1,17 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let t: st::Secret<(i32, i32), lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret((42, 84)) });
+  let result: st::Secret<((i32, i32), (i32, bool)), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let q: &(i32, i32) = unwrap_secret_ref(&t);
+    let larger: i32 = std::cmp::max(q.0, q.1);
+    let smaller: i32 = std::cmp::Ord::min(q.0, q.1);
+    let clamped: i32 = std::cmp::Ord::clamp(std::cmp::Ord::max(q.0, q.1), 0, 50);
+    wrap_secret(((larger, smaller), (clamped, larger >= smaller)))
+  });
+  assert_eq!(result.declassify().get_value_consume(), ((84, 42), (50, true)));
+}
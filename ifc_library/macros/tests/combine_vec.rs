@@ -0,0 +1,21 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+pub fn max_ref(a: &i32, b: &i32) -> i32 {
+  if *a >= *b { *a } else { *b }
+}
+
+pub fn main() {
+  let mut worths: Vec<st::Secret<i32, lat::Label_ABC>> = Vec::new();
+  worths.push(secret_structs::secret_block!(lat::Label_ABC { wrap_secret(300) }));
+  worths.push(secret_structs::secret_block!(lat::Label_ABC { wrap_secret(1200) }));
+  worths.push(secret_structs::secret_block!(lat::Label_ABC { wrap_secret(700) }));
+  let init: st::Secret<i32, lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC { wrap_secret(i32::MIN) });
+  let richest: st::Secret<i32, lat::Label_ABC> = st::combine_vec(&worths, init, |a, b| unsafe { max_ref(a, b) });
+  assert_eq!(richest.declassify().get_value_consume(), 1200);
+}
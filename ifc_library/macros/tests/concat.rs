@@ -0,0 +1,17 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let alices: st::Secret<Vec<i32>, lat::Label_A> = st::Secret::fill_from_public_iter(vec![1, 2]);
+  let bobs: st::Secret<Vec<i32>, lat::Label_B> = st::Secret::fill_from_public_iter(vec![3, 4, 5]);
+  let both: st::Secret<Vec<i32>, lat::Label_AB> = alices.concat(bobs);
+  assert_eq!(both.declassify().get_value_consume(), vec![1, 2, 3, 4, 5]);
+
+  let none: st::Secret<Vec<i32>, lat::Label_A> = st::Secret::fill_from_public_iter(vec![]);
+  let bobs: st::Secret<Vec<i32>, lat::Label_B> = st::Secret::fill_from_public_iter(vec![6]);
+  let only_bobs: st::Secret<Vec<i32>, lat::Label_ABC> = none.concat(bobs);
+  assert_eq!(only_bobs.declassify().get_value_consume(), vec![6]);
+}
@@ -0,0 +1,19 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let pair: (st::Secret<String, lat::Label_A>, st::Secret<i32, lat::Label_A>) = secret_structs::secret_block!(lat::Label_A {
+    (wrap_secret(std::string::String::from("alice")), wrap_secret(1000000))
+  });
+  let (name, worth): (String, i32) = st::declassify_all(pair);
+  assert_eq!(name, "alice");
+  assert_eq!(worth, 1000000);
+
+  let mut many: Vec<st::Secret<i32, lat::Label_B>> = Vec::new();
+  many.push(secret_structs::secret_block!(lat::Label_B { wrap_secret(1) }));
+  many.push(secret_structs::secret_block!(lat::Label_B { wrap_secret(2) }));
+  assert_eq!(st::declassify_all(many), vec![1, 2]);
+}
@@ -0,0 +1,18 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Reveals only which bracket of 10,000 a salary falls in
+#[side_effect_free_attr]
+fn bracket(salary: i64) -> i64 {
+  salary / 10000 * 10000
+}
+
+pub fn main() {
+  let salary: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(73250i64) });
+  let bucket: i64 = salary.declassify_via(|s| unsafe { bracket(s) });
+  assert_eq!(bucket, 70000);
+}
@@ -0,0 +1,38 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::InvisibleSideEffectFreeDerive;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(InvisibleSideEffectFreeDerive, Default, Clone, Copy)]
+struct Code {
+  v: u64,
+}
+
+// The derived Default only defaults a u64, so it has no side effects
+unsafe impl st::SafeDefault for Code {}
+
+impl Code {
+  // An inherent `default` would win over the trait method for `Code::default()`; it must not be called
+  #[allow(dead_code)]
+  fn default() -> Code {
+    println!("side effect");
+    Code { v: 99 }
+  }
+}
+
+pub fn main() {
+  let secret_v: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5u64) });
+  let codes: st::Secret<(Code, Code), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut a: Code = Default::default();
+    let b: Code = Code::default();
+    let c: Vec<u8> = std::vec::Vec::default();
+    a.v = *unwrap_secret_ref(&secret_v) + std::vec::Vec::len(&c) as u64;
+    wrap_secret((a, b))
+  });
+  let (a, b) = codes.declassify().get_value_consume();
+  assert_eq!(a.v, 5);
+  assert_eq!(b.v, 0);
+}
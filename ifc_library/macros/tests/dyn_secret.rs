@@ -0,0 +1,32 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(1i32) });
+  let b: st::Secret<i32, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(2i32) });
+  let c: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3i32) });
+  let mixed: Vec<st::DynSecret<i32>> = vec![a.erase(), b.erase(), c.erase()];
+
+  assert!(mixed[0].is::<lat::Label_A>());
+  assert!(!mixed[1].is::<lat::Label_A>());
+  assert!(mixed[1].label_name().ends_with("Label_B"));
+
+  let mut sum_a = 0;
+  let mut sum_b = 0;
+  for secret in mixed {
+    if secret.is::<lat::Label_A>() {
+      sum_a += secret.downcast::<lat::Label_A>().unwrap().declassify().get_value_consume();
+    } else {
+      sum_b += secret.downcast::<lat::Label_B>().unwrap().declassify().get_value_consume();
+    }
+  }
+  assert_eq!(sum_a, 4);
+  assert_eq!(sum_b, 2);
+
+  // The label has to match exactly, even if it could flow.
+  let d: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(4i32) });
+  assert!(d.erase().downcast::<lat::Label_AB>().is_none());
+}
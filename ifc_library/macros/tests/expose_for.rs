@@ -0,0 +1,19 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let password: st::Secret<String, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(std::string::String::from("hunter2"))
+  });
+  let len: usize = password.expose_for(|p| p.len());
+  assert_eq!(len, 7);
+
+  // The secret itself is still usable afterwards
+  let upper: st::Secret<usize, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(std::string::String::len(unwrap_secret_ref(&password)) * 2)
+  });
+  assert_eq!(upper.declassify().get_value_consume(), 14);
+}
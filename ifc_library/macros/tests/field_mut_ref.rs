@@ -0,0 +1,32 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::InvisibleSideEffectFreeDerive;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(InvisibleSideEffectFreeDerive, Default)]
+struct Body {
+  position: [f64; 3],
+  mass: f64,
+}
+
+pub fn main() {
+  let mut body: st::Secret<Body, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(Body { position: [1.0f64, 2.0f64, 3.0f64], mass: 4.0f64 })
+  });
+  // Borrow single fields of the secret mutably and update them in place.
+  secret_structs::secret_block_no_return!(lat::Label_A {
+    let mass = &mut unwrap_secret_mut_ref(&mut body).mass;
+    *mass *= 2.0f64;
+    let position = &mut unwrap_secret_mut_ref(&mut body).position;
+    position[1] = 5.0f64;
+    unwrap_secret_mut_ref(&mut body).position[2] += 1.0f64;
+  });
+  let result: st::Secret<(f64, f64, f64), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let body = unwrap_secret_ref(&body);
+    wrap_secret((body.mass, body.position[1], body.position[2]))
+  });
+  assert_eq!(result.declassify().get_value_consume(), (8.0, 5.0, 4.0));
+}
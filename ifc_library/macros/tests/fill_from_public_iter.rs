@@ -0,0 +1,14 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let width = 4;
+  let r0: st::Secret<Vec<f64>, lat::Label_A> = st::Secret::fill_from_public_iter((0..width).map(|i| i as f64));
+  assert_eq!(r0.declassify().get_value_consume(), vec![0.0, 1.0, 2.0, 3.0]);
+
+  let empty: st::Secret<Vec<u8>, lat::Label_A> = st::Secret::fill_from_public_iter(Vec::new());
+  assert!(empty.declassify().get_value_consume().is_empty());
+}
@@ -0,0 +1,18 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+pub fn count_if_busy(count: i32, hour: &u8) -> i32 {
+  if *hour >= 9 && *hour < 17 { count + 1 } else { count }
+}
+
+pub fn main() {
+  let hours: Vec<u8> = vec![8, 9, 12, 17, 16, 20];
+  let count: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(0) });
+  let count: st::Secret<i32, lat::Label_A> = count.fold_into(&hours, |c, h| unsafe { count_if_busy(c, h) });
+  assert_eq!(count.declassify().get_value_consume(), 3);
+}
@@ -0,0 +1,26 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+fn shade(x: usize) -> u8 {
+  (x as u8) * 2u8
+}
+
+pub fn main() {
+  let raw: Vec<u8> = vec![0, 0, 0, 0];
+  let mut row: st::Secret<Vec<u8>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(raw) });
+  secret_structs::secret_block_no_return!(lat::Label_A {
+    let row = unwrap_secret_mut_ref(&mut row);
+    // The mandelbrot pattern: assign a vetted result into a secret row
+    for x in 0usize..4usize {
+      (&mut *row)[x] = shade(x);
+    }
+    row[0] = 9u8;
+    row[3] += 1u8;
+  });
+  assert_eq!(row.declassify().get_value_consume(), vec![9, 2, 4, 7]);
+}
@@ -0,0 +1,57 @@
+#!/bin/bash
+
+# Builds the n-body benchmark to assembly and checks that no call to a side_effect_free_attr
+# trampoline is left, i.e., that the SIMD kernels are inlined. Expects the release builds that
+# autotest.sh makes. Please run shellcheck if you edit this file.
+
+set -eou pipefail
+
+TESTS_DIR=$(cd "$(dirname "$0")" && pwd)
+readonly TESTS_DIR
+
+MACROS_RELEASE_DIR="${TESTS_DIR}/../target/release"
+readonly MACROS_RELEASE_DIR
+
+STRUCTS_RELEASE_DIR="${TESTS_DIR}/../../secret_structs/target/release"
+readonly STRUCTS_RELEASE_DIR
+
+NBODY_SRC="${TESTS_DIR}/../../../ifc_examples/benchmark_games/n-body/src/main.rs"
+readonly NBODY_SRC
+
+if [[ "$(uname -m)" != "x86_64" ]]; then
+  echo "TEST SKIPPED: inline_trampolines.sh (n-body needs x86_64)"
+  exit 0
+fi
+
+LIBRARY_EXT=".so"
+if [[ "$(uname)" = "Darwin" ]]; then
+  LIBRARY_EXT=".dylib"
+fi
+readonly LIBRARY_EXT
+
+WORK_DIR=$(mktemp -d)
+readonly WORK_DIR
+trap 'rm -rf "${WORK_DIR}"' EXIT
+
+set +e
+compile_output=$(rustc --edition 2021 -C opt-level=3 --emit asm \
+  --extern secret_macros="${MACROS_RELEASE_DIR}/libsecret_macros${LIBRARY_EXT}" \
+  --extern secret_structs="${STRUCTS_RELEASE_DIR}/libsecret_structs.rlib" \
+  -L dependency="${STRUCTS_RELEASE_DIR}/deps" \
+  "${NBODY_SRC}" -o "${WORK_DIR}/n-body.s" 2>&1)
+exit_code=$?
+set -e
+
+if [[ "${exit_code}" -ne 0 ]]; then
+  echo "TEST FAILED: inline_trampolines.sh"
+  echo "Output:"
+  echo "${compile_output}"
+  exit 1
+fi
+
+if grep -E "call.*_secret_trampoline" "${WORK_DIR}/n-body.s"; then
+  echo "TEST FAILED: inline_trampolines.sh"
+  echo "The calls above were not inlined."
+  exit 1
+fi
+echo "TEST PASSED: inline_trampolines.sh"
@@ -0,0 +1,36 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::{side_effect_free_attr, InvisibleSideEffectFreeDerive};
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(InvisibleSideEffectFreeDerive, Default)]
+struct Millionaire {
+  name: st::Secret<String, lat::Label_Empty>,
+  net_worth: i64,
+}
+
+#[side_effect_free_attr]
+fn name_of(m: &Millionaire) -> &st::Secret<String, lat::Label_Empty> {
+  &m.name
+}
+
+pub fn main() {
+  let name: st::Secret<String, lat::Label_Empty> = secret_structs::secret_block!(lat::Label_Empty {
+    wrap_secret(std::string::String::from("Alice"))
+  });
+  let alice: st::Secret<Millionaire, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(Millionaire { name: name, net_worth: 1200 })
+  });
+
+  let public_name: &str = alice.inspect_public(|m| unsafe { name_of(m) });
+  assert_eq!(public_name, "Alice");
+
+  // The net worth itself stays secret
+  let worth: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(unwrap_secret_ref(&alice).net_worth)
+  });
+  assert_eq!(worth.declassify().get_value_consume(), 1200);
+}
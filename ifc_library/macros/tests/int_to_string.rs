@@ -0,0 +1,41 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let digits: st::Secret<u32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(31415u32) });
+  let text: st::Secret<String, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let n = unwrap_secret_ref(&digits);
+    let mut s = u32::to_string(n);
+    std::string::String::push_str(&mut s, "\t:");
+    std::string::String::push_str(&mut s, &u8::to_string(&7u8));
+    wrap_secret(s)
+  });
+  assert_eq!(text.declassify_ref(), "31415\t:7");
+
+  let parsed: st::Secret<Option<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    match <i32 as std::str::FromStr>::from_str("-42") {
+      Ok(v) => wrap_secret(std::option::Option::Some(v)),
+      Err(_) => wrap_secret(None),
+    }
+  });
+  assert_eq!(parsed.declassify().get_value_consume(), Some(-42));
+
+  let hex: st::Secret<Option<u64>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    match u64::from_str_radix("ff", 16u32) {
+      Ok(v) => wrap_secret(std::option::Option::Some(v)),
+      Err(_) => wrap_secret(None),
+    }
+  });
+  assert_eq!(hex.declassify().get_value_consume(), Some(255));
+
+  let bad: st::Secret<Option<u8>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    match <u8 as std::str::FromStr>::from_str("300") {
+      Ok(v) => wrap_secret(std::option::Option::Some(v)),
+      Err(_) => wrap_secret(None),
+    }
+  });
+  assert_eq!(bad.declassify().get_value_consume(), None);
+}
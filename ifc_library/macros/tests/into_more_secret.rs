@@ -0,0 +1,22 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+fn raise<T, L1, L2>(s: st::Secret<T, L1>) -> st::Secret<T, L2>
+where
+  T: st::SecretValueSafe,
+  L1: lat::Label,
+  L2: lat::Label,
+  st::Secret<T, L2>: From<st::Secret<T, L1>>,
+{
+  s.into()
+}
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(7) });
+  let ab: st::Secret<i32, lat::Label_AB> = a.into();
+  let top: st::Secret<i32, lat::Label_Top> = raise(ab);
+  assert_eq!(top.declassify().get_value_consume(), 7);
+}
@@ -0,0 +1,25 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use std::marker::PhantomData;
+use secret_macros::InvisibleSideEffectFreeDerive;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// A label-parameterized wrapper; the label is only a marker
+#[derive(InvisibleSideEffectFreeDerive, Default)]
+struct Tagged<L> {
+  v: i32,
+  _marker: PhantomData<L>,
+}
+
+pub fn main() {
+  let t: st::Secret<Tagged<lat::Label_A>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(Tagged { v: 4, _marker: std::marker::PhantomData })
+  });
+  let v: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(unwrap_secret_ref(&t).v * 2)
+  });
+  assert_eq!(v.declassify().get_value_consume(), 8);
+}
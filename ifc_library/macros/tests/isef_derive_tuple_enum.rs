@@ -0,0 +1,29 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(secret_macros::InvisibleSideEffectFreeDerive, Clone, Copy, Default)]
+struct Meters(i64);
+
+#[derive(secret_macros::InvisibleSideEffectFreeDerive, Clone, Copy, Default, PartialEq, Debug)]
+enum Reading {
+  #[default]
+  Missing,
+  Value(i64),
+  Range { lo: i64, hi: i64 },
+}
+
+pub fn main() {
+  let m = Meters(7);
+  let r = Reading::Range { lo: 1, hi: 3 };
+  let result: st::Secret<(Meters, Reading), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret((m, r))
+  });
+  let (m, r) = result.declassify().get_value_consume();
+  assert_eq!(m.0, 7);
+  assert_eq!(r, Reading::Range { lo: 1, hi: 3 });
+  assert_ne!(Reading::Missing, Reading::Value(0));
+}
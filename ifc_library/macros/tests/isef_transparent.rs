@@ -0,0 +1,27 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::InvisibleSideEffectFreeDerive;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Stands in for a big-integer newtype such as pidigits' Integer(rug::Integer): base-10 digits,
+// least significant first
+#[derive(InvisibleSideEffectFreeDerive, Default)]
+#[isef(transparent)]
+struct Digits(Vec<u32>);
+
+pub fn main() {
+  let digits: Vec<u32> = vec![1, 4];
+  let mut secret_digits: st::Secret<Digits, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(Digits { 0: digits })
+  });
+  // Allowlisted Vec operations reach the wrapped value without unchecked_operation
+  let len: st::Secret<usize, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let d: &mut Digits = unwrap_secret_mut_ref(&mut secret_digits);
+    std::vec::Vec::push(d.inner_mut(), 1u32);
+    wrap_secret(std::vec::Vec::len(d.inner()))
+  });
+  assert_eq!(len.declassify().get_value_consume(), 3);
+}
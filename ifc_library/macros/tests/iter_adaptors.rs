@@ -0,0 +1,23 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let n: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(7) });
+  let data: Vec<Vec<i32>> = vec![vec![1, 2], vec![], vec![3]];
+  let rows: st::Secret<Vec<Vec<i32>>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(data) });
+  let result: st::Secret<(Vec<i32>, Vec<i32>, Vec<i32>), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let n: i32 = *unwrap_secret_ref(&n);
+    let evens_down: Vec<i32> = std::iter::Iterator::rev(std::iter::Iterator::step_by(0..n, 2)).collect();
+    let mut it = std::iter::Iterator::chain(std::iter::Iterator::skip(0..n, 5), 10..12);
+    let first: i32 = std::option::Option::unwrap(std::iter::Iterator::next(std::iter::Iterator::by_ref(&mut it)));
+    let rest: Vec<i32> = std::iter::Iterator::take(it, 2).map(|x| x + first).collect();
+    let rows: &Vec<Vec<i32>> = unwrap_secret_ref(&rows);
+    let arr: &[Vec<i32>] = &rows[..];
+    let flat: Vec<i32> = std::iter::Iterator::flatten(<[_]>::iter(arr)).map(|x| *x * 10).collect();
+    wrap_secret((evens_down, rest, flat))
+  });
+  assert_eq!(result.declassify().get_value_consume(), (vec![6, 4, 2, 0], vec![11, 15], vec![10, 20, 30]));
+}
@@ -0,0 +1,30 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let data: Vec<i32> = vec![4, 5, 6];
+  let v: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(data) });
+  let result: st::Secret<((i32, i32), Vec<i32>, Vec<i32>), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut sum = 0;
+    for x in [1, 2, 3].into_iter() {
+      sum += x;
+    }
+    let v: &Vec<i32> = unwrap_secret_ref(&v);
+    let slice: &[i32] = &v[..];
+    let cloned: Vec<i32> = slice.iter().cloned().collect();
+    let copied: Vec<i32> = v.iter().copied().map(|x| x * 10).collect();
+    let arr = [7, 8];
+    let mut arr_sum = 0;
+    for x in arr.iter() {
+      arr_sum += *x;
+    }
+    for x in v.into_iter() {
+      arr_sum += *x;
+    }
+    wrap_secret(((sum, arr_sum), cloned, copied))
+  });
+  assert_eq!(result.declassify().get_value_consume(), ((6, 30), vec![4, 5, 6], vec![40, 50, 60]));
+}
@@ -0,0 +1,35 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let counts: st::Secret<Vec<u64>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut v = std::vec::Vec::new();
+    for c in [3u64, 1u64, 4u64, 1u64, 5u64] {
+      std::vec::Vec::push(&mut v, c);
+    }
+    wrap_secret(v)
+  });
+  let result: st::Secret<((u64, u64), (Option<u64>, Option<u64>), usize), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let counts: &Vec<u64> = unwrap_secret_ref(&counts);
+    let total: u64 = counts.iter().sum();
+    let product = counts.iter().product::<u64>();
+    let max = counts.iter().copied().max();
+    let last = counts.iter().copied().last();
+    wrap_secret(((total, product), (max, last), counts.iter().count()))
+  });
+  assert_eq!(result.declassify().get_value_consume(), ((14, 60), (Some(5), Some(5)), 5));
+
+  // The predicates are closures written in the block, so they are body-checked like any other.
+  let result: st::Secret<((Option<usize>, Option<u64>), (bool, bool), Option<u64>), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let counts: &Vec<u64> = unwrap_secret_ref(&counts);
+    let position = counts.iter().position(|c| *c == 4u64);
+    let found = counts.iter().copied().find(|c| *c > 3u64);
+    let any = counts.iter().any(|c| *c == 9u64);
+    let all = counts.iter().all(|c| *c >= 1u64);
+    wrap_secret(((position, found), (any, all), counts.iter().copied().min()))
+  });
+  assert_eq!(result.declassify().get_value_consume(), ((Some(2), Some(4)), (false, true), Some(1)));
+}
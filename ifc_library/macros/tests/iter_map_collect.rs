@@ -0,0 +1,16 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let n: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5) });
+  let result: st::Secret<(Vec<i32>, Vec<i32>), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let n: i32 = *unwrap_secret_ref(&n);
+    let squares: Vec<i32> = (0..n).map(|i| i * i).collect::<Vec<_>>();
+    let shifted: Vec<i32> = (0..n).map(|i| i * i).map(|x| x + 1).collect();
+    wrap_secret((squares, shifted))
+  });
+  assert_eq!(result.declassify().get_value_consume(), (vec![0, 1, 4, 9, 16], vec![1, 2, 5, 10, 17]));
+}
@@ -0,0 +1,22 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let parts: Vec<String> = vec![String::from("GGT"), String::from("GGTA"), String::from("GGTATT")];
+  let parts: st::Secret<Vec<String>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(parts) });
+  let joined: st::Secret<String, lat::Label_A> = parts.join_str(", ");
+  assert_eq!(joined.declassify().get_value_consume(), "GGT, GGTA, GGTATT");
+
+  let one: st::Secret<Vec<String>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut v = std::vec::Vec::new();
+    std::vec::Vec::push(&mut v, std::string::String::from("x"));
+    wrap_secret(v)
+  });
+  assert_eq!(one.join_str(", ").declassify().get_value_consume(), "x");
+
+  let none: st::Secret<Vec<String>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(std::vec::Vec::new()) });
+  assert_eq!(none.join_str(", ").declassify().get_value_consume(), "");
+}
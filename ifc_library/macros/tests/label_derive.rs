@@ -0,0 +1,22 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::Label;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(Clone, Default, Label)]
+#[more_secret_than(lat::Label_Empty)]
+pub struct Public {}
+
+#[derive(Clone, Default, Label)]
+#[more_secret_than(Public, lat::Label_Empty)]
+pub struct Hospital {}
+
+pub fn main() {
+  let visits: st::Secret<i32, Public> = secret_structs::secret_block!(Public { wrap_secret(3) });
+  let cost: st::Secret<i32, Hospital> = secret_structs::secret_block!(Hospital {
+    wrap_secret(*unwrap_secret_ref(&visits) * 100)
+  });
+  assert_eq!(cost.declassify().get_value_consume(), 300);
+}
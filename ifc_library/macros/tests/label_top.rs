@@ -0,0 +1,29 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::Label;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(Clone, Default, Label)]
+#[more_secret_than(lat::Label_Empty)]
+pub struct Hospital {}
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(1) });
+  let abc: st::Secret<i32, lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC { wrap_secret(2) });
+  let public: st::Secret<i32, lat::Label_Empty> = secret_structs::secret_block!(lat::Label_Empty { wrap_secret(3) });
+  let visits: st::Secret<i32, Hospital> = secret_structs::secret_block!(Hospital { wrap_secret(4) });
+
+  // Built-in and derived labels all flow up to Label_Top
+  let a: st::Secret<i32, lat::Label_Top> = a.cast_label();
+  let abc: st::Secret<i32, lat::Label_Top> = abc.cast_label();
+  let public: st::Secret<i32, lat::Label_Top> = public.cast_label();
+  let visits: st::Secret<i32, lat::Label_Top> = visits.cast_label();
+
+  let sum: st::Secret<i32, lat::Label_Top> = secret_structs::secret_block!(lat::Label_Top {
+    wrap_secret(*unwrap_secret_ref(&a) + *unwrap_secret_ref(&abc) + *unwrap_secret_ref(&public) + *unwrap_secret_ref(&visits))
+  });
+  // Only declassification gets data back out
+  assert_eq!(sum.declassify().get_value_consume(), 10);
+}
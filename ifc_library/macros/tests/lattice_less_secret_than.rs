@@ -0,0 +1,36 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::lattice::{LessSecretThan, MoreSecretThan};
+
+fn more_secret<A: MoreSecretThan<B>, B>() {}
+fn flows_into<Src: LessSecretThan<Dst>, Dst>() {}
+
+pub fn main() {
+  // Reflexivity for every label
+  more_secret::<lat::Label_Empty, lat::Label_Empty>();
+  more_secret::<lat::Label_A, lat::Label_A>();
+  more_secret::<lat::Label_B, lat::Label_B>();
+  more_secret::<lat::Label_C, lat::Label_C>();
+  more_secret::<lat::Label_AB, lat::Label_AB>();
+  more_secret::<lat::Label_AC, lat::Label_AC>();
+  more_secret::<lat::Label_BC, lat::Label_BC>();
+  more_secret::<lat::Label_ABC, lat::Label_ABC>();
+  flows_into::<lat::Label_Empty, lat::Label_Empty>();
+  flows_into::<lat::Label_A, lat::Label_A>();
+  flows_into::<lat::Label_B, lat::Label_B>();
+  flows_into::<lat::Label_C, lat::Label_C>();
+  flows_into::<lat::Label_AB, lat::Label_AB>();
+  flows_into::<lat::Label_AC, lat::Label_AC>();
+  flows_into::<lat::Label_BC, lat::Label_BC>();
+  flows_into::<lat::Label_ABC, lat::Label_ABC>();
+
+  // Label_Empty <= Label_A <= Label_AB <= Label_ABC
+  flows_into::<lat::Label_Empty, lat::Label_A>();
+  flows_into::<lat::Label_A, lat::Label_AB>();
+  flows_into::<lat::Label_AB, lat::Label_ABC>();
+  flows_into::<lat::Label_A, lat::Label_ABC>();
+  more_secret::<lat::Label_ABC, lat::Label_AB>();
+  more_secret::<lat::Label_AB, lat::Label_A>();
+}
@@ -0,0 +1,28 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let present: Option<i32> = Some(20);
+  let missing: Option<i32> = None;
+  let present: st::Secret<Option<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(present) });
+  let missing: st::Secret<Option<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(missing) });
+
+  let doubled: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let Some(x) = *unwrap_secret_ref(&present) else {
+      return wrap_secret(-1);
+    };
+    wrap_secret(x * 2)
+  });
+  assert_eq!(doubled.declassify().get_value_consume(), 40);
+
+  let fallback: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let Some(x): Option<i32> = *unwrap_secret_ref(&missing) else {
+      return wrap_secret(-1);
+    };
+    wrap_secret(x * 2)
+  });
+  assert_eq!(fallback.declassify().get_value_consume(), -1);
+}
@@ -0,0 +1,25 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Metavariables are substituted as invisible groups, which the secret block must see through
+macro_rules! scaled_sum {
+  ($label:ty, $a:expr, $b:expr, $k:expr) => {
+    secret_structs::secret_block!($label {
+      let a: i64 = *unwrap_secret_ref(&$a);
+      let b: i64 = *unwrap_secret_ref(&$b);
+      let mut total: i64 = $k * (a + b);
+      total = total + $k;
+      wrap_secret(total)
+    })
+  };
+}
+
+pub fn main() {
+  let a: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(2i64) });
+  let b: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3i64) });
+  let sum: st::Secret<i64, lat::Label_A> = scaled_sum!(lat::Label_A, a, b, 1i64 + 2i64);
+  assert_eq!(sum.declassify().get_value_consume(), 18);
+}
@@ -0,0 +1,30 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+fn square(x: &f64) -> f64 {
+  *x * *x
+}
+
+#[side_effect_free_attr]
+fn is_negative(x: &f64) -> bool {
+  *x < 0.0
+}
+
+pub fn main() {
+  let xs: st::Secret<[f64; 4], lat::Label_A> =
+    secret_structs::secret_block!(lat::Label_A { wrap_secret([1.5f64, -2.0f64, 0.0f64, 3.0f64]) });
+
+  let squares: st::Secret<[f64; 4], lat::Label_A> = xs.map_array(|x| unsafe { square(x) });
+  assert_eq!(squares.declassify().get_value_consume(), [2.25, 4.0, 0.0, 9.0]);
+
+  // The element type can change
+  let xs: st::Secret<[f64; 4], lat::Label_A> =
+    secret_structs::secret_block!(lat::Label_A { wrap_secret([1.5f64, -2.0f64, 0.0f64, 3.0f64]) });
+  let negative: st::Secret<[bool; 4], lat::Label_A> = xs.map_array(|x| unsafe { is_negative(x) });
+  assert_eq!(negative.declassify().get_value_consume(), [false, true, false, false]);
+}
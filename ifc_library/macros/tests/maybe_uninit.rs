@@ -0,0 +1,20 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let secret_base: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(7) });
+  let result: st::Secret<([i32; 4], i32), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let zeroed: std::mem::MaybeUninit<[i32; 4]> = std::mem::MaybeUninit::zeroed();
+    let mut arr: [i32; 4] = unsafe { std::mem::MaybeUninit::assume_init(zeroed) };
+    arr[1] = *unwrap_secret_ref(&secret_base);
+    let mut slot: std::mem::MaybeUninit<i32> = std::mem::MaybeUninit::uninit();
+    let written: &mut i32 = std::mem::MaybeUninit::write(&mut slot, arr[1] + 1);
+    *written += 1;
+    let initialized: std::mem::MaybeUninit<i32> = std::mem::MaybeUninit::new(unsafe { std::mem::MaybeUninit::assume_init(slot) });
+    wrap_secret((arr, unsafe { std::mem::MaybeUninit::assume_init(initialized) }))
+  });
+  assert_eq!(result.declassify().get_value_consume(), ([0, 7, 0, 0], 9));
+}
@@ -0,0 +1,27 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let name: st::Secret<String, lat::Label_A> =
+    secret_structs::secret_block!(lat::Label_A { wrap_secret(std::string::String::from("  Alice  ")) });
+  // Allowlisted str methods can be chained with method syntax, on a String or a &str
+  let trimmed: st::Secret<String, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let n: &String = unwrap_secret_ref(&name);
+    wrap_secret(n.trim().to_string())
+  });
+  assert_eq!(trimmed.declassify().get_value_consume(), "Alice");
+
+  let count: st::Secret<String, lat::Label_A> =
+    secret_structs::secret_block!(lat::Label_A { wrap_secret(std::string::String::from(" 42 ")) });
+  let parsed: st::Secret<u32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let r: Result<u32, std::num::ParseIntError> = <u32 as std::str::FromStr>::from_str(unwrap_secret_ref(&count).trim());
+    wrap_secret(match r {
+      Ok(v) => v,
+      Err(_) => 0u32,
+    })
+  });
+  assert_eq!(parsed.declassify().get_value_consume(), 42);
+}
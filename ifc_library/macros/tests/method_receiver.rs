@@ -0,0 +1,42 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::{side_effect_free_attr, InvisibleSideEffectFreeDerive};
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Not Copy, so a by-value receiver is moved
+#[derive(Default, InvisibleSideEffectFreeDerive)]
+struct Samples {
+  items: Vec<u32>,
+}
+
+#[side_effect_free_attr]
+impl Samples {
+  fn len(&self) -> usize {
+    std::vec::Vec::len(&self.items)
+  }
+
+  fn into_items(self) -> Vec<u32> {
+    self.items
+  }
+}
+
+pub fn main() {
+  let samples: st::Secret<Samples, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut items = std::vec::Vec::new();
+    std::vec::Vec::push(&mut items, 3u32);
+    std::vec::Vec::push(&mut items, 4u32);
+    wrap_secret(Samples { items: items })
+  });
+  let result: st::Secret<(usize, usize, Vec<u32>), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let samples: &Samples = unwrap_secret_ref(&samples);
+    let owned = Samples { items: std::vec::Vec::clone(&samples.items) };
+    // The same calls through a value, a reference and an explicit borrow
+    let n = owned.len();
+    let m = (&owned).len();
+    wrap_secret((n, m + samples.len(), owned.into_items()))
+  });
+  assert_eq!(result.declassify().get_value_consume(), (2, 4, vec![3, 4]));
+}
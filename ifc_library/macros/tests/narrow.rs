@@ -0,0 +1,15 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let small: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(42i64) });
+  let narrowed: st::Secret<Result<i32, ()>, lat::Label_A> = small.narrow();
+  assert_eq!(narrowed.declassify().get_value_consume(), Ok(42));
+
+  let big: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5000000000i64) });
+  let narrowed: st::Secret<Result<i32, ()>, lat::Label_A> = big.narrow();
+  assert_eq!(narrowed.declassify().get_value_consume(), Err(()));
+}
@@ -0,0 +1,21 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let x: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5) });
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    use secret_macros::side_effect_free_attr;
+    const OFFSET: i32 = 3;
+    static SCALE: i32 = 2;
+    #[side_effect_free_attr]
+    fn scale(v: i32, by: i32) -> i32 {
+      v * by
+    }
+    let v: i32 = *unwrap_secret_ref(&x);
+    wrap_secret(scale(v, SCALE) + OFFSET)
+  });
+  assert_eq!(result.declassify().get_value_consume(), 13);
+}
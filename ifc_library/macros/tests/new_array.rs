@@ -0,0 +1,19 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+fn twice(i: usize) -> i32 {
+  (i as i32) * 2
+}
+
+pub fn main() {
+  let doubled: st::Secret<[i32; 4], lat::Label_A> = st::Secret::new_array(|i| unsafe { twice(i) });
+  assert_eq!(doubled.declassify().get_value_consume(), [0, 2, 4, 6]);
+
+  let empty: st::Secret<[i32; 0], lat::Label_A> = st::Secret::new_array(|i| unsafe { twice(i) });
+  assert_eq!(empty.declassify().get_value_consume(), []);
+}
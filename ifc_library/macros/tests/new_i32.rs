@@ -0,0 +1,13 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let s = unsafe { st::Secret::<i32, lat::Label_A>::new(42) };
+  let doubled: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(*unwrap_secret_ref(&s) * 2)
+  });
+  assert_eq!(doubled.declassify().get_value_consume(), 84);
+}
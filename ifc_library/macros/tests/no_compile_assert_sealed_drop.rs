@@ -0,0 +1,21 @@
+// error-pattern: `Guard` has a Drop impl, which can have invisible side effects
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::assert_sealed;
+use secret_structs::secret as st;
+
+struct Guard(i32);
+unsafe impl st::InvisibleSideEffectFree for Guard {}
+
+impl Drop for Guard {
+  fn drop(&mut self) {
+    println!("dropped {}", self.0);
+  }
+}
+
+assert_sealed!(Guard);
+
+pub fn main() {
+  let _g = Guard(1);
+}
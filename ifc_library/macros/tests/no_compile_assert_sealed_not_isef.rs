@@ -0,0 +1,13 @@
+// error-pattern: `Plain` is not InvisibleSideEffectFree
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::assert_sealed;
+
+struct Plain(i32);
+
+assert_sealed!(Plain);
+
+pub fn main() {
+  let _p = Plain(1);
+}
@@ -0,0 +1,20 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// error-pattern: use unchecked_operation(...) if the assignment is intended
+pub fn main() {
+  let mut sec_val: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5) });
+  let sec_val2: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(9) });
+  let secreter_val: st::Secret<i32, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(7) });
+  // Would make sec_val depend on secreter_val, which has label B, while sec_val only has label A.
+  secret_structs::secret_block_no_return!(lat::Label_B {
+    let unwrapped_secreter_val = unwrap_secret(secreter_val);
+    if unwrapped_secreter_val > 0 {
+      sec_val = sec_val2;
+    }
+  });
+  println!("{}", sec_val.declassify().get_value_consume());
+}
@@ -0,0 +1,21 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// compile-flags: --edition 2021
+// error-pattern: async/await is not permitted in secret blocks
+async fn get() -> i32 {
+  42
+}
+
+pub async fn run() {
+  let _result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(get().await)
+  });
+}
+
+pub fn main() {
+  let _ = run();
+}
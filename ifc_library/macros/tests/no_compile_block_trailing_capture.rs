@@ -0,0 +1,26 @@
+// error-pattern: value of type `MyStruct` captured by secret block is not InvisibleSideEffectFree
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+struct MyStruct {
+  data: i32,
+}
+
+pub fn main() {
+  let x = MyStruct { data: 3 };
+  // A captured `x` as an inner block's trailing expression is checked like any other capture.
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let y = {
+      let z = 1i32;
+      x
+    };
+    wrap_secret(5i32)
+  });
+  println!(
+    "Result: {}",
+    result.declassify().get_value_consume(),
+  );
+}
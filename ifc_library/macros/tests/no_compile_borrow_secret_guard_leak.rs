@@ -0,0 +1,16 @@
+// error-pattern: VisibleSideEffectFree
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let mut counter: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(41) });
+  let mut leaked: i32 = 0;
+  counter.borrow_secret().map(|x| {
+    leaked = *x;
+    unsafe { st::Vetted::wrap(()) }
+  });
+  println!("{}", leaked);
+}
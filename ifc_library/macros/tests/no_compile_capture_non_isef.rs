@@ -0,0 +1,22 @@
+// error-pattern: value of type `MyStruct` captured by secret block is not InvisibleSideEffectFree
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+struct MyStruct {
+  data: i32,
+}
+
+pub fn main() {
+  let x = MyStruct { data: 3 };
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let y = x;
+    wrap_secret(5)
+  });
+  println!(
+    "Result: {}",
+    result.declassify().get_value_consume(),
+  );
+}
@@ -0,0 +1,14 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let t: st::Secret<i32, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB { wrap_secret(42) });
+  let result: st::Secret<i32, lat::Label_A> = t.cast_label();
+  println!(
+    "Result: {}",
+    result.declassify().get_value_consume(),
+  );
+}
@@ -0,0 +1,18 @@
+// error-pattern: secret cannot flow into
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let v: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut v = std::vec::Vec::new();
+    std::vec::Vec::push(&mut v, 10i32);
+    wrap_secret(v)
+  });
+  let idx: st::Secret<usize, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(0usize) });
+  // Whether a Label_B index hit can't be labeled only Label_A
+  let found: st::Secret<Option<&i32>, lat::Label_A> = v.checked_index(idx);
+  println!("{}", found);
+}
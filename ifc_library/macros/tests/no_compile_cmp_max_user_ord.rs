@@ -0,0 +1,31 @@
+// error-pattern: `Evil`'s comparison is not known to be side-effect free
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+static SEEN: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd)]
+struct Evil {
+  x: i32,
+}
+unsafe impl st::InvisibleSideEffectFree for Evil {}
+
+// A user cmp can copy the secret somewhere public
+impl Ord for Evil {
+  fn cmp(&self, other: &Evil) -> std::cmp::Ordering {
+    SEEN.store(self.x, std::sync::atomic::Ordering::SeqCst);
+    self.x.cmp(&other.x)
+  }
+}
+
+pub fn main() {
+  let s: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(42) });
+  let _: st::Secret<Evil, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let e: Evil = Evil { x: *unwrap_secret_ref(&s) };
+    wrap_secret(std::cmp::Ord::max(e, Evil { x: 0 }))
+  });
+  println!("{}", SEEN.load(std::sync::atomic::Ordering::SeqCst));
+}
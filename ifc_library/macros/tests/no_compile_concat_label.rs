@@ -0,0 +1,14 @@
+// error-pattern: a `Label_B` secret cannot flow into `Label_A`
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let alices: st::Secret<Vec<i32>, lat::Label_A> = st::Secret::fill_from_public_iter(vec![1, 2]);
+  let bobs: st::Secret<Vec<i32>, lat::Label_B> = st::Secret::fill_from_public_iter(vec![3]);
+  // The result reveals Bob's list, so it can't be only Alice's
+  let both: st::Secret<Vec<i32>, lat::Label_A> = alices.concat(bobs);
+  println!("{:?}", both.declassify().get_value_consume());
+}
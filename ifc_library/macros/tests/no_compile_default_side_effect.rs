@@ -0,0 +1,32 @@
+// error-pattern: `Evil`'s Default impl is not known to be side-effect free
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+static CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[derive(Clone, Copy)]
+struct Evil;
+unsafe impl st::InvisibleSideEffectFree for Evil {}
+
+// Counting calls would reveal which branch a secret condition took
+impl Default for Evil {
+  fn default() -> Evil {
+    CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    Evil
+  }
+}
+
+pub fn main() {
+  let b: st::Secret<bool, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(true) });
+  let _: st::Secret<bool, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    if *unwrap_secret_ref(&b) {
+      let e: Evil = Default::default();
+      let f: Evil = Evil::default();
+    }
+    wrap_secret(true)
+  });
+  println!("{}", CALLS.load(std::sync::atomic::Ordering::SeqCst));
+}
@@ -0,0 +1,15 @@
+// error-pattern: lifetime may not live long enough
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let password: st::Secret<String, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(std::string::String::from("hunter2"))
+  });
+  // The borrow can't escape the closure
+  let leaked: &String = password.expose_for(|p| p);
+  println!("{}", leaked);
+}
@@ -0,0 +1,26 @@
+// error-pattern: secret cannot flow into
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::InvisibleSideEffectFreeDerive;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(InvisibleSideEffectFreeDerive, Default)]
+struct Body {
+  mass: f64,
+}
+
+pub fn main() {
+  let secret: st::Secret<f64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(4.0f64) });
+  let mut public: st::Secret<Body, lat::Label_Empty> = secret_structs::secret_block!(lat::Label_Empty {
+    wrap_secret(Body { mass: 0.0f64 })
+  });
+  // Writing a Label_A value into a field of a public secret would leak it
+  secret_structs::secret_block_no_return!(lat::Label_A {
+    let mass = &mut unwrap_secret_mut_ref(&mut public).mass;
+    *mass = *unwrap_secret_ref(&secret);
+  });
+  println!("{}", public.get_value_ref().mass);
+}
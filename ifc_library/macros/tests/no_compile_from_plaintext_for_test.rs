@@ -0,0 +1,12 @@
+// error-pattern: from_plaintext_for_test
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Only available with cfg(test) or the "testutil" feature of secret_structs
+pub fn main() {
+  let s: st::Secret<i32, lat::Label_A> = st::Secret::from_plaintext_for_test(42);
+  println!("{}", s.peek_for_test());
+}
@@ -0,0 +1,13 @@
+// error-pattern: the trait `IsPublic` is not implemented for `Label_A`
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let secret: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5) });
+  // Consuming a secret has to go through declassify too.
+  let value: i32 = secret.get_value_consume();
+  println!("{}", value);
+}
@@ -0,0 +1,12 @@
+// error-pattern: the trait `IsPublic` is not implemented for `Label_A`
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let secret: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5) });
+  // Reading a secret has to go through declassify.
+  println!("{}", secret.get_value_ref());
+}
@@ -0,0 +1,27 @@
+// error-pattern: the trait `IsPublic` is not implemented for `Label_A`
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::{side_effect_free_attr, InvisibleSideEffectFreeDerive};
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(InvisibleSideEffectFreeDerive, Default)]
+struct Account {
+  pin: st::Secret<u32, lat::Label_A>,
+}
+
+#[side_effect_free_attr]
+fn pin_of(a: &Account) -> &st::Secret<u32, lat::Label_A> {
+  &a.pin
+}
+
+pub fn main() {
+  let pin: st::Secret<u32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(1234u32) });
+  let account: st::Secret<Account, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(Account { pin: pin })
+  });
+  let leaked: &u32 = account.inspect_public(|a| unsafe { pin_of(a) });
+  println!("{}", leaked);
+}
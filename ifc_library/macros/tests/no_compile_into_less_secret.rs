@@ -0,0 +1,12 @@
+// error-pattern: the trait `From<Secret<i32, Label_AB>>` is not implemented for `Secret<i32, Label_A>`
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let ab: st::Secret<i32, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB { wrap_secret(7) });
+  let a: st::Secret<i32, lat::Label_A> = ab.into();
+  println!("{}", a.declassify().get_value_consume());
+}
@@ -0,0 +1,18 @@
+// error-pattern: required by a bound in `check_field_cache_of_Holder`
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+#[derive(secret_macros::InvisibleSideEffectFreeDerive)]
+struct Holder {
+  id: u32,
+  cache: HashMap<u32, Cell<u32>>,
+}
+
+pub fn main() {
+  let h = Holder { id: 1, cache: HashMap::new() };
+  println!("{} {}", h.id, h.cache.len());
+}
@@ -0,0 +1,16 @@
+// error-pattern: macros are not allowed in secret blocks
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let n: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5) });
+  // The predicate passed to any is still body-checked
+  let result: st::Secret<bool, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let n: i32 = *unwrap_secret_ref(&n);
+    wrap_secret((0..n).any(|i| { println!("{}", i); i == 3 }))
+  });
+  println!("{}", result);
+}
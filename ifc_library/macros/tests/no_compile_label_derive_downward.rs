@@ -0,0 +1,20 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::Label;
+use secret_structs::secret as st;
+
+#[derive(Clone, Default, Label)]
+pub struct Public {}
+
+#[derive(Clone, Default, Label)]
+#[more_secret_than(Public)]
+pub struct Hospital {}
+
+// A Hospital secret can't flow into a Public block.
+pub fn main() {
+  let cost: st::Secret<i32, Hospital> = secret_structs::secret_block!(Hospital { wrap_secret(300) });
+  let _leak: st::Secret<i32, Public> = secret_structs::secret_block!(Public {
+    wrap_secret(*unwrap_secret_ref(&cost))
+  });
+}
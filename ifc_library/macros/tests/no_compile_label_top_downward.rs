@@ -0,0 +1,12 @@
+// error-pattern: the trait `MoreSecretThan<Label_Top>` is not implemented for `Label_ABC`
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let top: st::Secret<i32, lat::Label_Top> = secret_structs::secret_block!(lat::Label_Top { wrap_secret(1) });
+  let abc: st::Secret<i32, lat::Label_ABC> = top.cast_label();
+  println!("{}", abc.declassify().get_value_consume());
+}
@@ -0,0 +1,12 @@
+// error-pattern: LessSecretThan
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::lattice::LessSecretThan;
+
+fn flows_into<Src: LessSecretThan<Dst>, Dst>() {}
+
+pub fn main() {
+  flows_into::<lat::Label_AB, lat::Label_A>();
+}
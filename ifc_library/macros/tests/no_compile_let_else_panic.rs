@@ -0,0 +1,17 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// error-pattern: Function calls & macros are not allowed in secret blocks.
+pub fn main() {
+  let missing: Option<i32> = None;
+  let missing: st::Secret<Option<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(missing) });
+  let _x: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let Some(x) = *unwrap_secret_ref(&missing) else {
+      panic!("missing");
+    };
+    wrap_secret(x)
+  });
+}
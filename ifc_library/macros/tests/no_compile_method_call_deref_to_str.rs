@@ -0,0 +1,27 @@
+// error-pattern: `Spy` is not a string
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(Clone)]
+struct Spy(String);
+unsafe impl st::InvisibleSideEffectFree for Spy {}
+
+// Deref could leak, so it must not run just because `.trim()` is an allowlisted str method
+impl std::ops::Deref for Spy {
+  type Target = str;
+  fn deref(&self) -> &str {
+    println!("leaked {}", self.0);
+    &self.0
+  }
+}
+
+pub fn main() {
+  let spy: Spy = Spy(std::string::String::from(" secret "));
+  let trimmed: st::Secret<String, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(spy.trim().to_string())
+  });
+  println!("{}", trimmed.declassify().get_value_consume());
+}
@@ -0,0 +1,18 @@
+// error-pattern: as `Vetted<i32>`
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    // Nested fns are passed through, but calling one still requires it to be vetted
+    fn leak(v: i32) -> i32 {
+      println!("{}", v);
+      v
+    }
+    wrap_secret(leak(1))
+  });
+  println!("{}", result);
+}
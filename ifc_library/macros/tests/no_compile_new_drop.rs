@@ -0,0 +1,20 @@
+// error-pattern: `Droppable` is not InvisibleSideEffectFree
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+struct Droppable {
+  f: i32,
+}
+
+impl Drop for Droppable {
+  fn drop(&mut self) {
+    println!("dropped {}", self.f);
+  }
+}
+
+pub fn main() {
+  let _s = unsafe { st::Secret::<_, lat::Label_A>::new(Droppable { f: 8 }) };
+}
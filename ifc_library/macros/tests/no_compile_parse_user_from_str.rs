@@ -0,0 +1,29 @@
+// error-pattern: non-primitive cast: `Result<Evil, ()>` as `Vetted
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// A user FromStr impl can do anything with the string, e.g., copy it somewhere public
+// Being InvisibleSideEffectFree says nothing about from_str
+struct Evil;
+unsafe impl st::InvisibleSideEffectFree for Evil {}
+
+impl std::str::FromStr for Evil {
+  type Err = ();
+  fn from_str(s: &str) -> Result<Evil, ()> {
+    println!("leaked {}", s);
+    Err(())
+  }
+}
+
+pub fn main() {
+  let password: st::Secret<String, lat::Label_A> =
+    secret_structs::secret_block!(lat::Label_A { wrap_secret(std::string::String::from("hunter2")) });
+  let _: st::Secret<bool, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let p: &String = unwrap_secret_ref(&password);
+    let r: Result<Evil, ()> = p.parse::<Evil>();
+    wrap_secret(true)
+  });
+}
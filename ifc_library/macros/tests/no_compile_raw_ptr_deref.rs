@@ -0,0 +1,16 @@
+// error-pattern: cannot be dereferenced in a secret block
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+
+// The body of a side_effect_free_attr fn is unsafe, so only the secret block check rejects this
+#[side_effect_free_attr]
+fn read_raw(p: *const i32) -> i32 {
+  *p
+}
+
+pub fn main() {
+  let x: i32 = 5;
+  println!("{}", unsafe { read_raw(&x as *const i32) }.unwrap());
+}
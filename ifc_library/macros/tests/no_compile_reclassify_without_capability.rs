@@ -0,0 +1,14 @@
+// error-pattern: call to unsafe function
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let joined: st::Secret<i32, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB { wrap_secret(7i32) });
+  // Granting a capability is only allowed in an unsafe block, which marks it for review
+  let token = st::DeclassifyCapability::<lat::Label_AB, lat::Label_A>::new();
+  let lowered: st::Secret<i32, lat::Label_A> = joined.reclassify(&token);
+  println!("{}", lowered);
+}
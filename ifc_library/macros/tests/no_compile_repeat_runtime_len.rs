@@ -0,0 +1,15 @@
+// error-pattern: array repeat length must be a public constant
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let n: st::Secret<usize, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(4usize) });
+  let zeros: st::Secret<[u8; 4], lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let n = *unwrap_secret_ref(&n);
+    wrap_secret([0u8; n])
+  });
+  println!("{:?}", zeros.declassify().get_value_consume());
+}
@@ -0,0 +1,17 @@
+// error-pattern: principal `Bob` is not in `(Alice,)`
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+struct Alice;
+struct Bob;
+impl lat::Principal for Alice {}
+impl lat::Principal for Bob {}
+
+pub fn main() {
+  let both: st::Secret<i32, lat::Secrecy<(Alice, Bob)>> = secret_structs::secret_block!(lat::Secrecy<(Alice, Bob)> { wrap_secret(42) });
+  let alice: st::Secret<i32, lat::Secrecy<(Alice,)>> = both.cast_label();
+  println!("{}", alice.declassify().get_value_consume());
+}
@@ -0,0 +1,22 @@
+// error-pattern: `Droppable` is not InvisibleSideEffectFree
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+
+struct Droppable;
+
+impl Drop for Droppable {
+  fn drop(&mut self) {
+    println!("side effect");
+  }
+}
+
+#[side_effect_free_attr]
+fn id<T>(x: T) -> T {
+  x
+}
+
+pub fn main() {
+  let _ = unsafe { id(Droppable) };
+}
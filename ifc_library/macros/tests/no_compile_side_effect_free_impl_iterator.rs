@@ -0,0 +1,30 @@
+// error-pattern: `Countdown` is not InvisibleSideEffectFree
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+
+// Not InvisibleSideEffectFree: next() could do anything
+struct Countdown {
+  n: u64,
+}
+
+impl Iterator for Countdown {
+  type Item = u64;
+  fn next(&mut self) -> Option<u64> {
+    println!("leaked {}", self.n);
+    self.n = self.n.checked_sub(1)?;
+    Some(self.n)
+  }
+}
+
+// The opaque type hides an iterator that isn't InvisibleSideEffectFree
+#[side_effect_free_attr]
+fn countdown(n: u64) -> impl Iterator<Item = u64> {
+  Countdown { n: n }
+}
+
+pub fn main() {
+  let v: Vec<u64> = unsafe { countdown(3).unwrap() }.collect();
+  println!("{:?}", v);
+}
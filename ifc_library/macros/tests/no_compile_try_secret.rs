@@ -0,0 +1,17 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// error-pattern: the trait `IsPublic` is not implemented for `Label_A`
+fn leak(r: st::Secret<Result<i32, ()>, lat::Label_A>) -> Result<i32, ()> {
+  // Would reveal whether the secret Result is Ok or Err.
+  let _x: st::Secret<i32, lat::Label_A> = r?;
+  Ok(0)
+}
+
+pub fn main() {
+  let r: st::Secret<Result<i32, ()>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5i64) }).narrow::<i32>();
+  let _ = leak(r);
+}
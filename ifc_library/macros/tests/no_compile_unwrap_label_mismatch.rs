@@ -0,0 +1,12 @@
+// error-pattern: a `Label_B` secret cannot be unwrapped in a `Label_A` secret block
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let b: st::Secret<i32, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(42) });
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(unwrap_secret(b)) });
+  println!("{}", a.declassify().get_value_consume());
+}
@@ -0,0 +1,16 @@
+// error-pattern: cannot assign to `leak`, which is captured from outside the secret block
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3) });
+  let mut leak: i32 = 0;
+  let v: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let x: i32 = unwrap_secret(a);
+    wrap_secret(vec![{ leak = x; 1 }])
+  });
+  println!("{} {:?}", leak, v.declassify().get_value_consume());
+}
@@ -0,0 +1,14 @@
+// error-pattern: vec![value; length] isn't supported in secret blocks
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let n: st::Secret<usize, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3usize) });
+  let v: st::Secret<Vec<u8>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(vec![0u8; unwrap_secret(n)])
+  });
+  println!("{:?}", v.declassify().get_value_consume());
+}
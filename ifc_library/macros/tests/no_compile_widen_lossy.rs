@@ -0,0 +1,13 @@
+// error-pattern: the trait bound `u8: SafeCast<u64>` is not satisfied
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let wide: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(300u64) });
+  // u64 to u8 can lose bits, so it has to go through narrow
+  let byte: st::Secret<u8, lat::Label_A> = wide.widen();
+  println!("{}", byte);
+}
@@ -0,0 +1,20 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let rows: Vec<Vec<i32>> = vec![vec![0, 0, 0], vec![0, 0, 0]];
+  let mut grid: st::Secret<Vec<Vec<i32>>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(rows) });
+  secret_structs::secret_block_no_return!(lat::Label_A {
+    let grid = unwrap_secret_mut_ref(&mut grid);
+    (&mut *grid)[1][2] = 7;
+    (&mut *grid)[0][0] += 3;
+  });
+  let cell: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let grid = unwrap_secret_ref(&grid);
+    wrap_secret(grid[1][2] + grid[0][0])
+  });
+  assert_eq!(cell.declassify().get_value_consume(), 10);
+}
@@ -0,0 +1,24 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+fn is_even(x: &i32) -> bool {
+  (*x & 1i32) == 0i32
+}
+
+pub fn main() {
+  let xs: st::Secret<Vec<i32>, lat::Label_A> = st::Secret::fill_from_public_iter(vec![3, 4, -2, 7, 0, 9]);
+  let (evens, odds): (st::Secret<Vec<i32>, lat::Label_A>, st::Secret<Vec<i32>, lat::Label_A>) =
+    xs.partition(|x| unsafe { is_even(x) });
+  assert_eq!(evens.declassify().get_value_consume(), vec![4, -2, 0]);
+  assert_eq!(odds.declassify().get_value_consume(), vec![3, 7, 9]);
+
+  let empty: st::Secret<Vec<i32>, lat::Label_A> = st::Secret::fill_from_public_iter(vec![]);
+  let (evens, odds) = empty.partition(|x| unsafe { is_even(x) });
+  assert!(evens.declassify().get_value_consume().is_empty());
+  assert!(odds.declassify().get_value_consume().is_empty());
+}
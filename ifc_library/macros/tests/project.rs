@@ -0,0 +1,34 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::InvisibleSideEffectFreeDerive;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Same shape as Example<T, L> in secret_structs/src/main.rs
+#[derive(Default, InvisibleSideEffectFreeDerive)]
+pub struct Example<T, L>
+where
+  T: st::SecretValueSafe + st::InvisibleSideEffectFree,
+  L: lat::Label,
+{
+  val: T,
+  secret_val: st::Secret<T, L>,
+}
+
+pub fn main() {
+  let inner: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(10) });
+  let example: st::Secret<Example<i64, lat::Label_A>, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB {
+    wrap_secret(Example { val: 5, secret_val: inner })
+  });
+  let projected: st::Secret<&st::Secret<i64, lat::Label_A>, lat::Label_AB> =
+    example.project(|e: &Example<i64, lat::Label_A>| unsafe { st::Vetted::wrap(&e.secret_val) });
+  let val: st::Secret<&i64, lat::Label_AB> = example.project(|e: &Example<i64, lat::Label_A>| unsafe { st::Vetted::wrap(&e.val) });
+  let result: st::Secret<i64, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB {
+    let s: &st::Secret<i64, lat::Label_A> = *unwrap_secret_ref(&projected);
+    let v: &i64 = *unwrap_secret_ref(&val);
+    wrap_secret(*unwrap_secret_ref(s) + *v)
+  });
+  assert_eq!(result.declassify().get_value_consume(), 15);
+}
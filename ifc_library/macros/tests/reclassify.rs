@@ -0,0 +1,24 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// The one place in this program that may lower Label_AB to Label_A.
+mod policy {
+  use super::lat;
+  use super::st::DeclassifyCapability;
+
+  pub fn ab_to_a() -> DeclassifyCapability<lat::Label_AB, lat::Label_A> {
+    unsafe { DeclassifyCapability::new() }
+  }
+}
+
+pub fn main() {
+  let joined: st::Secret<i32, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB { wrap_secret(7i32) });
+  let lowered: st::Secret<i32, lat::Label_A> = joined.reclassify(&policy::ab_to_a());
+  let doubled: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(*unwrap_secret_ref(&lowered) * 2i32)
+  });
+  assert_eq!(doubled.declassify().get_value_consume(), 14);
+}
@@ -0,0 +1,18 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+const ROW: usize = 3;
+
+pub fn main() {
+  let zeros: st::Secret<[u8; 4], lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret([0u8; 4]) });
+  assert_eq!(zeros.declassify().get_value_consume(), [0; 4]);
+
+  let x: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(7) });
+  let grid: st::Secret<[i32; 6], lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret([*unwrap_secret_ref(&x); ROW * 2])
+  });
+  assert_eq!(grid.declassify().get_value_consume(), [7; 6]);
+}
@@ -0,0 +1,26 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let mut current: st::Secret<Vec<u8>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut v = std::vec::Vec::new();
+    std::vec::Vec::push(&mut v, 1u8);
+    wrap_secret(v)
+  });
+  let mut next: st::Secret<Vec<u8>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut v = std::vec::Vec::new();
+    std::vec::Vec::push(&mut v, 2u8);
+    std::vec::Vec::push(&mut v, 3u8);
+    wrap_secret(v)
+  });
+
+  // Swap the two buffers without declassifying either.
+  let old_current = current.replace(next.take());
+  next = old_current;
+  assert_eq!(current.declassify().get_value_consume(), vec![2, 3]);
+  assert_eq!(next.take().declassify().get_value_consume(), vec![1]);
+  assert!(next.declassify().get_value_consume().is_empty());
+}
@@ -0,0 +1,13 @@
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  // Yao's millionaires: learn who is richer without learning either net worth
+  let alice: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(4_200_000u64) });
+  let bob: st::Secret<u64, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(3_900_000u64) });
+  assert!(alice.reveal_gt(&bob, "millionaires: who is richer"));
+  assert!(!bob.reveal_gt(&alice, "millionaires: who is richer"));
+  assert!(!alice.reveal_eq(&bob, "millionaires: equally rich"));
+}
@@ -0,0 +1,25 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Counts hits ('H') and misses ('M') in a battleship guess log
+#[side_effect_free_attr]
+fn tally(counts: (u32, u32), byte: u8) -> (u32, u32) {
+  if byte == b'H' {
+    (counts.0 + 1, counts.1)
+  } else if byte == b'M' {
+    (counts.0, counts.1 + 1)
+  } else {
+    counts
+  }
+}
+
+pub fn main() {
+  let raw: Vec<u8> = b"HMM,H,MMH".to_vec();
+  let log: st::Secret<Vec<u8>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(raw) });
+  let counts: st::Secret<(u32, u32), lat::Label_A> = log.scan_bytes((0, 0), |c, b| unsafe { tally(c, b) });
+  assert_eq!(counts.declassify().get_value_consume(), (3, 4));
+}
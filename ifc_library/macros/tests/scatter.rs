@@ -0,0 +1,24 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  // A pidigits-style line: public tab and colon around five secret digits
+  let template: &[u8] = b"?????\t:5";
+  let digits: st::Secret<[u8; 5], lat::Label_A> =
+    secret_structs::secret_block!(lat::Label_A { wrap_secret([b'3', b'1', b'4', b'1', b'5']) });
+  let line: st::Secret<Vec<u8>, lat::Label_A> = st::Secret::scatter(template, &[0, 1, 2, 3, 4], digits);
+  assert_eq!(line.declassify().get_value_consume(), b"31415\t:5".to_vec());
+
+  // Positions not listed keep the template's element
+  let digits: st::Secret<[u8; 2], lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret([b'9', b'2']) });
+  let line: st::Secret<Vec<u8>, lat::Label_A> = st::Secret::scatter(template, &[4, 0], digits);
+  assert_eq!(line.declassify().get_value_consume(), b"2???9\t:5".to_vec());
+
+  // An out-of-bounds position panics before any secret value is read
+  let digits: st::Secret<[u8; 1], lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret([b'7']) });
+  let result = std::panic::catch_unwind(|| st::Secret::<Vec<u8>, lat::Label_A>::scatter(template, &[8], digits));
+  assert!(result.is_err());
+}
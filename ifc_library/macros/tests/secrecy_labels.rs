@@ -0,0 +1,25 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+struct Alice;
+struct Bob;
+impl lat::Principal for Alice {}
+impl lat::Principal for Bob {}
+
+type AliceOnly = lat::Secrecy<(Alice,)>;
+type AliceAndBob = lat::Secrecy<(Alice, Bob)>;
+
+pub fn main() {
+  let a: st::Secret<i32, AliceOnly> = secret_structs::secret_block!(AliceOnly { wrap_secret(40) });
+  let b: st::Secret<i32, lat::Secrecy<(Bob,)>> = secret_structs::secret_block!(lat::Secrecy<(Bob,)> { wrap_secret(2) });
+  // A block labeled with both principals can read either one's secrets
+  let sum: st::Secret<i32, AliceAndBob> = secret_structs::secret_block!(AliceAndBob {
+    wrap_secret(unwrap_secret(a) + unwrap_secret(b))
+  });
+  // Order doesn't matter
+  let sum: st::Secret<i32, lat::Secrecy<(Bob, Alice)>> = sum.cast_label();
+  assert_eq!(sum.declassify().get_value_consume(), 42);
+}
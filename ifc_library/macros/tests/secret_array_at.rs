@@ -0,0 +1,17 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let arr: st::Secret<[i32; 5], lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret([10, 20, 30, 40, 50]) });
+  let third: st::Secret<&i32, lat::Label_A> = arr.at(2);
+  let elems: [st::Secret<&i32, lat::Label_A>; 5] = arr.each_ref();
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let x: &i32 = *unwrap_secret_ref(&third);
+    let last: &i32 = *unwrap_secret_ref(&elems[4]);
+    wrap_secret(*x + *last)
+  });
+  assert_eq!(result.declassify().get_value_consume(), 80);
+}
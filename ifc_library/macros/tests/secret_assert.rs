@@ -0,0 +1,37 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+fn index_of(v: &st::Secret<Vec<i32>, lat::Label_A>, i: &st::Secret<usize, lat::Label_A>) -> st::Secret<i32, lat::Label_A> {
+  secret_structs::secret_block!(lat::Label_A {
+    let v: &Vec<i32> = unwrap_secret_ref(v);
+    let i: usize = *unwrap_secret_ref(i);
+    secret_assert!(i < std::vec::Vec::len(v));
+    wrap_secret(v[i])
+  })
+}
+
+pub fn main() {
+  let data: Vec<i32> = vec![10, 20, 30];
+  let v: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(data) });
+  let i: st::Secret<usize, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(1usize) });
+  let bad: st::Secret<usize, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5usize) });
+
+  if std::env::var_os("SECRET_ASSERT_TRIP").is_some() {
+    // Aborts in debug builds; in release the assertion is compiled out and the block returns its default
+    let _ = index_of(&v, &bad);
+    return;
+  }
+
+  assert_eq!(index_of(&v, &i).declassify().get_value_consume(), 20);
+
+  // Trip the assertion in a child process, since it aborts
+  let status = std::process::Command::new(std::env::current_exe().unwrap())
+    .env("SECRET_ASSERT_TRIP", "1")
+    .stderr(std::process::Stdio::null())
+    .status()
+    .unwrap();
+  assert_eq!(status.success(), !cfg!(debug_assertions));
+}
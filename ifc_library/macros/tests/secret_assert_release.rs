@@ -0,0 +1,2 @@
+// compile-flags: -O
+include!("secret_assert.rs");
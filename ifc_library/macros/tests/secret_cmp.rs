@@ -0,0 +1,33 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use std::cmp::Ordering;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let a: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3i64) });
+  let b: st::Secret<i64, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(8i64) });
+
+  let ord: st::Secret<Option<Ordering>, lat::Label_AB> = a.secret_cmp(&b);
+  assert_eq!(ord.declassify().get_value_consume(), Some(Ordering::Less));
+
+  let ord: st::Secret<Ordering, lat::Label_AB> = b.secret_total_cmp(&a);
+  assert_eq!(ord.declassify().get_value_consume(), Ordering::Greater);
+
+  // Floats are only partially ordered, so a NaN gives a secret None
+  let x: st::Secret<f64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(1.5f64) });
+  let nan: st::Secret<f64, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(f64::NAN) });
+  let ord: st::Secret<Option<Ordering>, lat::Label_AB> = x.secret_cmp(&nan);
+  assert_eq!(ord.declassify().get_value_consume(), None);
+  let ord: st::Secret<Option<Ordering>, lat::Label_A> = x.secret_cmp(&x);
+  assert_eq!(ord.declassify().get_value_consume(), Some(Ordering::Equal));
+
+  // The outcome stays secret until declassified, so it can drive further secret logic.
+  let ord: st::Secret<Ordering, lat::Label_A> = a.secret_total_cmp(&a);
+  let smaller: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let o: Ordering = *unwrap_secret_ref(&ord);
+    if o == Ordering::Greater { wrap_secret(0i64) } else { wrap_secret(*unwrap_secret_ref(&a)) }
+  });
+  assert_eq!(smaller.declassify().get_value_consume(), 3);
+}
@@ -0,0 +1,34 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::secret_test;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// The RefCell spy from counter_examples: writing a secret into a captured RefCell would leak it.
+#[secret_test(
+  error = "value of type `RefCell<i32>` captured by secret block is not InvisibleSideEffectFree",
+  leak = {
+    let spy = std::cell::RefCell::new(0i32);
+    let secret: st::Secret<i32, lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC { wrap_secret(13i32) });
+    let _copy: st::Secret<i32, lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC {
+      let target = *unwrap_secret_ref(&secret);
+      *std::cell::RefCell::borrow_mut(&spy) = target;
+      wrap_secret(target)
+    });
+    println!("{}", spy.borrow());
+  }
+)]
+fn refcell_spy_is_rejected() {
+  let spy = 0i32;
+  let secret: st::Secret<i32, lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC { wrap_secret(13i32) });
+  let copy: st::Secret<i32, lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC {
+    let target = *unwrap_secret_ref(&secret);
+    wrap_secret(target + spy)
+  });
+  assert_eq!(copy.declassify().get_value_consume(), 13);
+}
+
+pub fn main() {
+  refcell_spy_is_rejected();
+}
@@ -0,0 +1,19 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+fn secret_len(s: &st::Secret<[u8], lat::Label_A>) -> st::Secret<usize, lat::Label_A> {
+  secret_structs::secret_block!(lat::Label_A {
+    let bytes: &[u8] = unwrap_secret_ref(s);
+    wrap_secret(<[_]>::len(bytes))
+  })
+}
+
+pub fn main() {
+  let owned: st::Secret<[u8; 4], lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret([1, 2, 3, 4]) });
+  let unsized_ref: &st::Secret<[u8], lat::Label_A> = &owned;
+  let len: st::Secret<usize, lat::Label_A> = secret_len(unsized_ref);
+  assert_eq!(len.declassify().get_value_consume(), 4);
+}
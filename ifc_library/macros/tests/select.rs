@@ -0,0 +1,32 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+fn pick(cond: bool, a: i64, b: i64) -> i64 {
+  let cond: st::Secret<bool, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(cond) });
+  let a: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(a) });
+  let b: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(b) });
+  cond.select(a, b).declassify().get_value_consume()
+}
+
+pub fn main() {
+  assert_eq!(pick(true, -5, 9), -5);
+  assert_eq!(pick(false, -5, 9), 9);
+  assert_eq!(pick(true, i64::MIN, i64::MAX), i64::MIN);
+
+  for &c in &[true, false] {
+    for &(x, y) in &[(true, false), (false, true), (true, true)] {
+      let cond: st::Secret<bool, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(c) });
+      let x_s: st::Secret<bool, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(x) });
+      let y_s: st::Secret<bool, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(y) });
+      assert_eq!(cond.select(x_s, y_s).declassify().get_value_consume(), if c { x } else { y });
+    }
+  }
+
+  let cond: st::Secret<bool, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(false) });
+  let a: st::Secret<u8, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(0xF0u8) });
+  let b: st::Secret<u8, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(0x0Fu8) });
+  assert_eq!(cond.select(a, b).declassify().get_value_consume(), 0x0F);
+}
@@ -0,0 +1,47 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+const N: usize = 2;
+
+// Both trampolines copy the signature, including the const-expression length
+#[side_effect_free_attr]
+fn middle(bytes: &[u8; N * 2 + 1]) -> u8 {
+  bytes[N]
+}
+
+#[side_effect_free_attr]
+fn first<const M: usize>(bytes: &[u8; M]) -> u8 {
+  bytes[0usize]
+}
+
+#[side_effect_free_attr]
+fn pad(bytes: [u8; N]) -> [u8; N * 2 + 1] {
+  let mut out = [0u8; N * 2 + 1];
+  out[0usize] = bytes[0usize];
+  out[1usize] = bytes[1usize];
+  out
+}
+
+struct Frame;
+
+#[side_effect_free_attr]
+impl Frame {
+  const PAIRS: usize = 1;
+
+  fn last(bytes: &[u8; Frame::PAIRS + 3]) -> u8 {
+    bytes[Frame::PAIRS + 2]
+  }
+}
+
+pub fn main() {
+  let result: st::Secret<((u8, u8), u8, [u8; N * 2 + 1]), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let bytes: [u8; N * 2 + 1] = [1u8, 2u8, 3u8, 4u8, 5u8];
+    let frame: [u8; Frame::PAIRS + 3] = [1u8, 2u8, 3u8, 9u8];
+    wrap_secret(((middle(&bytes), first(&bytes)), Frame::last(&frame), pad([7u8, 8u8])))
+  });
+  assert_eq!(result.declassify().get_value_consume(), ((3, 1), 9, [7, 8, 0, 0, 0]));
+}
@@ -0,0 +1,29 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// No InvisibleSideEffectFree bound needed; the attribute adds it.
+#[side_effect_free_attr]
+fn id<T>(x: T) -> T {
+  x
+}
+
+#[side_effect_free_attr]
+fn first<T, U>(pair: (T, U)) -> T where U: Copy {
+  pair.0
+}
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(id(7))
+  });
+  assert_eq!(a.declassify().get_value_consume(), 7);
+
+  let s: st::Secret<String, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    wrap_secret(first((std::string::String::from("x"), 1u8)))
+  });
+  assert_eq!(s.declassify().get_value_consume(), "x");
+}
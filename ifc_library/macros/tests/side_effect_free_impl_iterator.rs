@@ -0,0 +1,30 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// An opaque return type works as long as the concrete iterator is InvisibleSideEffectFree
+#[side_effect_free_attr]
+fn evens(n: u64) -> impl Iterator<Item = u64> {
+  std::iter::Iterator::step_by(0..n, 2)
+}
+
+// So does an opaque argument
+#[side_effect_free_attr]
+fn skip_first(it: impl Iterator<Item = u64>) -> impl Iterator<Item = u64> {
+  std::iter::Iterator::skip(it, 1)
+}
+
+pub fn main() {
+  let limit: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(9u64) });
+  let sum: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut sum: u64 = 0u64;
+    for x in skip_first(evens(unwrap_secret(limit))) {
+      sum += x;
+    }
+    wrap_secret(sum)
+  });
+  assert_eq!(sum.declassify().get_value_consume(), 2 + 4 + 6 + 8);
+}
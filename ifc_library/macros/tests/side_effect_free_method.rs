@@ -0,0 +1,48 @@
+#![feature(negative_impls)]
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::{side_effect_free_attr, InvisibleSideEffectFreeDerive};
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[derive(Clone, Copy, Default, InvisibleSideEffectFreeDerive)]
+struct Code {
+  v: u64,
+}
+
+impl Code {
+  // A method with a receiver can be marked on its own
+  #[side_effect_free_attr]
+  fn push(&mut self, c: u8, mask: u64) {
+    unchecked_operation(self.v <<= 2);
+    unchecked_operation(self.v |= c as u64);
+    unchecked_operation(self.v &= mask);
+  }
+}
+
+// Associated functions without a receiver need the attribute on the impl block
+#[side_effect_free_attr]
+impl Code {
+  fn make_mask(frame: usize) -> u64 {
+    unchecked_operation((1u64 << (2 * frame)) - 1)
+  }
+
+  fn value(&self) -> u64 {
+    self.v
+  }
+}
+
+pub fn main() {
+  let bases: Vec<u8> = vec![0, 1, 3, 2];
+  let code: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mask: u64 = Code::make_mask(3);
+    let mut code: Code = Code { v: 0 };
+    for c in bases.iter() {
+      code.push(*c, mask);
+    }
+    wrap_secret(code.value())
+  });
+  // Only the last three bases (1, 3, 2) fit in the mask
+  assert_eq!(code.declassify().get_value_consume(), 0b01_11_10);
+}
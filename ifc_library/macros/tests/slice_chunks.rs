@@ -0,0 +1,29 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let data: Vec<i32> = vec![1, 2, 3, 4, 5, 6, 7, 8];
+  let t: st::Secret<Vec<i32>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(data) });
+  let result: st::Secret<(Vec<i32>, (i32, i32)), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let v: &Vec<i32> = unwrap_secret_ref(&t);
+    let arr: &[i32] = &v[..];
+    let mut sums: Vec<i32> = std::vec::Vec::new();
+    for chunk in <[_]>::chunks_exact(arr, 4) {
+      let mut sum = 0;
+      for x in <[_]>::iter(chunk) {
+        sum += *x;
+      }
+      std::vec::Vec::push(&mut sums, sum);
+    }
+    let mut windows = 0;
+    for _window in <[_]>::windows(arr, 3) {
+      windows += 1;
+    }
+    let (left, _right) = <[_]>::split_at(arr, 3);
+    wrap_secret((sums, (windows, <[_]>::len(left) as i32)))
+  });
+  assert_eq!(result.declassify().get_value_consume(), (vec![10, 26], (6, 3)));
+}
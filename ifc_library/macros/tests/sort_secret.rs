@@ -0,0 +1,12 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let raw: Vec<i64> = vec![42, -7, 19, 0, 19, 3];
+  let mut scores: st::Secret<Vec<i64>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(raw) });
+  st::sort_secret(&mut scores);
+  assert_eq!(scores.declassify().get_value_consume(), vec![-7, 0, 3, 19, 19, 42]);
+}
@@ -0,0 +1,22 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let first: st::Secret<String, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(std::string::String::from("secret")) });
+  let suffix: &str = "-name";
+  let result: st::Secret<(String, bool), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut s: String = std::string::String::clone(unwrap_secret_ref(&first));
+    std::string::String::push_str(&mut s, suffix);
+    std::string::String::push(&mut s, '!');
+    std::string::String::insert(&mut s, 0, '<');
+    let last: Option<char> = std::string::String::pop(&mut s);
+    std::string::String::push(&mut s, std::option::Option::unwrap(last));
+    let empty: bool = std::string::String::is_empty(&s);
+    let view: &str = std::string::String::as_str(&s);
+    wrap_secret((str::to_string(view), empty))
+  });
+  assert_eq!(result.declassify().get_value_consume(), (String::from("<secret-name!"), false));
+}
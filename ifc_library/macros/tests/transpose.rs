@@ -0,0 +1,26 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let mut worths: Vec<st::Secret<i32, lat::Label_ABC>> = Vec::new();
+  worths.push(secret_structs::secret_block!(lat::Label_ABC { wrap_secret(300) }));
+  worths.push(secret_structs::secret_block!(lat::Label_ABC { wrap_secret(1200) }));
+  worths.push(secret_structs::secret_block!(lat::Label_ABC { wrap_secret(700) }));
+
+  // Vec<Secret> -> Secret<Vec>
+  let together: st::Secret<Vec<i32>, lat::Label_ABC> = st::transpose_in(worths);
+  assert_eq!(together.declassify().get_value_consume(), vec![300, 1200, 700]);
+
+  // Secret<[_; N]> -> [Secret; N] -> Secret<Vec>
+  let together: st::Secret<[i32; 3], lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC { wrap_secret([1, 2, 3]) });
+  let apart: [st::Secret<i32, lat::Label_ABC>; 3] = st::transpose_out(together);
+  let together: st::Secret<Vec<i32>, lat::Label_ABC> = st::transpose_in(Vec::from(apart));
+  assert_eq!(together.declassify().get_value_consume(), vec![1, 2, 3]);
+
+  let empty: st::Secret<[i32; 0], lat::Label_ABC> = secret_structs::secret_block!(lat::Label_ABC { wrap_secret([]) });
+  let apart: [st::Secret<i32, lat::Label_ABC>; 0] = st::transpose_out(empty);
+  assert!(apart.is_empty());
+}
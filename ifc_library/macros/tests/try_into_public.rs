@@ -0,0 +1,36 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+// Only lets Label_A secrets out, and only while the boundary is open
+struct Boundary {
+  open: bool,
+}
+
+impl st::DeclassifyPolicy for Boundary {
+  fn allows(&self, label: &'static str) -> bool {
+    self.open && label == std::any::type_name::<lat::Label_A>()
+  }
+}
+
+pub fn main() {
+  let salary: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(73250i64) });
+
+  // Denied: the secret comes back intact and still labeled
+  let salary: st::Secret<i64, lat::Label_A> = match salary.try_into_public(&Boundary { open: false }) {
+    Ok(_) => panic!("the closed boundary let the secret out"),
+    Err(secret) => secret,
+  };
+  assert_eq!(format!("{:?}", salary), "(secret)");
+
+  // Allowed: the plain value comes out
+  assert_eq!(salary.try_into_public(&Boundary { open: true }).ok(), Some(73250));
+
+  // The label is part of the decision, and closures are policies too
+  let other: st::Secret<i64, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(1i64) });
+  let other: st::Secret<i64, lat::Label_B> = other.try_into_public(&Boundary { open: true }).unwrap_err();
+  let only_b = |label: &'static str| label.ends_with("Label_B");
+  assert_eq!(other.try_into_public(&only_b).ok(), Some(1));
+}
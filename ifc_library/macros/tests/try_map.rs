@@ -0,0 +1,21 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+fn to_i32(x: i64) -> Result<i32, ()> {
+  unchecked_operation(<i32 as std::convert::TryFrom<i64>>::try_from(x).map_err(|_| ()))
+}
+
+pub fn main() {
+  let small: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(-12i64) });
+  let small: st::Secret<Result<i32, ()>, lat::Label_A> = small.try_map(|x| unsafe { to_i32(x) });
+  assert_eq!(small.declassify().get_value_consume(), Ok(-12));
+
+  let big: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(5000000000i64) });
+  let big: st::Secret<Result<i32, ()>, lat::Label_A> = big.try_map(|x| unsafe { to_i32(x) });
+  assert_eq!(big.declassify().get_value_consume(), Err(()));
+}
@@ -0,0 +1,29 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+fn narrow_public(x: i64) -> st::Secret<Result<i32, ()>, lat::Label_Empty> {
+  secret_structs::secret_block!(lat::Label_Empty { wrap_secret(x) }).narrow::<i32>()
+}
+
+// ? in a function returning Result
+fn to_i32(x: i64) -> Result<i32, ()> {
+  let n: st::Secret<i32, lat::Label_Empty> = narrow_public(x)?;
+  Ok(n.get_value_consume())
+}
+
+// ? in a function returning a public secret Result
+fn add_one(x: i64) -> st::Secret<Result<i32, ()>, lat::Label_Empty> {
+  let n: st::Secret<i32, lat::Label_Empty> = narrow_public(x)?;
+  let y: i32 = n.get_value_consume() + 1;
+  secret_structs::secret_block!(lat::Label_Empty { wrap_secret(y) }).narrow::<i32>()
+}
+
+pub fn main() {
+  assert_eq!(to_i32(42), Ok(42));
+  assert_eq!(to_i32(5000000000), Err(()));
+  assert_eq!(add_one(41).get_value_consume(), Ok(42));
+  assert_eq!(add_one(5000000000).get_value_consume(), Err(()));
+}
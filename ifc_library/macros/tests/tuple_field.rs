@@ -0,0 +1,25 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let pair: st::Secret<(i32, (u8, i64)), lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret((2, (3u8, 40i64))) });
+  let sum: st::Secret<i64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let op = unwrap_secret_ref(&pair);
+    let first = op.0;
+    let nested = (op.1).1 + op.1.1;
+    wrap_secret((first as i64) + nested + (op.1.0 as i64) + (*op).0 as i64)
+  });
+  assert_eq!(sum.declassify().get_value_consume(), 2 + 80 + 3 + 2);
+
+  let swapped: st::Secret<(i32, i32), lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let mut t = (1, 2);
+    let old = t.0;
+    t.0 = t.1;
+    t.1 = old;
+    wrap_secret(t)
+  });
+  assert_eq!(swapped.declassify().get_value_consume(), (2, 1));
+}
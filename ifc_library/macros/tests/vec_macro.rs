@@ -0,0 +1,23 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let a: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(3) });
+  let b: st::Secret<i32, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(4) });
+  let v: st::Secret<Vec<i32>, lat::Label_AB> = secret_structs::secret_block!(lat::Label_AB {
+    let x: i32 = unwrap_secret(a);
+    let y: i32 = unwrap_secret(b);
+    wrap_secret(vec![x, y, x * y,])
+  });
+  assert_eq!(v.declassify().get_value_consume(), vec![3, 4, 12]);
+
+  // Nested and empty vec! work too
+  let grid: st::Secret<Vec<Vec<u8>>, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let empty: Vec<u8> = vec![];
+    wrap_secret(vec![vec![1u8, 2u8], empty])
+  });
+  assert_eq!(grid.declassify().get_value_consume(), vec![vec![1, 2], vec![]]);
+}
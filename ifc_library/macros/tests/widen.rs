@@ -0,0 +1,16 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let byte: st::Secret<u8, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(200u8) });
+  // No secret block needed, and the label stays Label_A.
+  let wide: st::Secret<u64, lat::Label_A> = byte.widen();
+  assert_eq!(wide.declassify().get_value_consume(), 200);
+
+  let neg: st::Secret<i16, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(-5i16) });
+  let wide: st::Secret<i64, lat::Label_A> = neg.widen();
+  assert_eq!(wide.declassify().get_value_consume(), -5);
+}
@@ -0,0 +1,23 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use secret_macros::side_effect_free_attr;
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+#[side_effect_free_attr]
+pub fn add_secrets(a: i32, b: i32) -> i32 {
+  a + b
+}
+
+pub fn main() {
+  // A manually vetted value
+  let t: st::Secret<(i32, i32), lat::Label_A> = st::with_label::<lat::Label_A, (i32, i32)>(|| unsafe { st::Vetted::wrap((42, 84)) });
+  // A value vetted by a side_effect_free_attr function
+  let s: st::Secret<i32, lat::Label_A> = st::with_label(|| unsafe { add_secrets(1, 2) });
+  let result: st::Secret<i32, lat::Label_A> = secret_structs::secret_block!(lat::Label_A {
+    let q: &(i32, i32) = unwrap_secret_ref(&t);
+    wrap_secret(q.0 + q.1 + *unwrap_secret_ref(&s))
+  });
+  assert_eq!(result.declassify().get_value_consume(), 129);
+}
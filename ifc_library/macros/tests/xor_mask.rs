@@ -0,0 +1,26 @@
+extern crate secret_macros;
+extern crate secret_structs;
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use secret_structs::lattice as lat;
+use secret_structs::secret as st;
+
+pub fn main() {
+  let key: st::Secret<u64, lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret(0xdead_beef_u64) });
+  // A random pad held by B
+  let random: u64 = RandomState::new().build_hasher().finish();
+  let pad: st::Secret<u64, lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret(random) });
+
+  // Split the key into two shares, then recombine them.
+  let share: st::Secret<u64, lat::Label_AB> = key.xor_mask(pad.clone());
+  let recombined: st::Secret<u64, lat::Label_AB> = share.xor_mask(pad);
+  assert_eq!(recombined.declassify().get_value_consume(), 0xdead_beef);
+
+  // Byte arrays are XORed element-wise.
+  let bytes: st::Secret<[u8; 3], lat::Label_A> = secret_structs::secret_block!(lat::Label_A { wrap_secret([1u8, 2u8, 3u8]) });
+  let mask: st::Secret<[u8; 3], lat::Label_B> = secret_structs::secret_block!(lat::Label_B { wrap_secret([0xffu8, 0u8, 3u8]) });
+  let masked: st::Secret<[u8; 3], lat::Label_AB> = bytes.xor_mask(mask);
+  assert_eq!(masked.declassify().get_value_consume(), [0xfe, 2, 0]);
+}
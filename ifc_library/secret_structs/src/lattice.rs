@@ -1,5 +1,6 @@
 #![allow(non_camel_case_types)]
 use crate::secret::VisibleSideEffectFree;
+use std::marker::PhantomData;
 
 #[derive(Clone, Default)]
 pub struct Label_A {}
@@ -22,6 +23,11 @@ pub struct Label_Empty {}
 #[derive(Clone, Default)]
 pub struct Label_ABC {}
 
+// More secret than every other label, including ones from #[derive(Label)], e.g., as a sink
+// label. Data only leaves it by declassification.
+#[derive(Clone, Default)]
+pub struct Label_Top {}
+
 unsafe impl Label for Label_Empty {}
 unsafe impl Label for Label_A {}
 unsafe impl Label for Label_B {}
@@ -30,14 +36,36 @@ unsafe impl Label for Label_AB {}
 unsafe impl Label for Label_AC {}
 unsafe impl Label for Label_BC {}
 unsafe impl Label for Label_ABC {}
+unsafe impl Label for Label_Top {}
 
 // TODO: If Label isn't declared unsafe, this is still allowed.
 // Why is the supertrait (VisibleSideEffectFree) of a safe trait allowed by the compiler??
-pub unsafe trait Label: Default + VisibleSideEffectFree /*+ UnwindSafe*/ {}
+pub unsafe trait Label: Default + VisibleSideEffectFree + 'static /*+ UnwindSafe*/ {}
 
 // Define the secrecy level lattice using this trait
+#[rustc_on_unimplemented(
+    message = "a `{T}` secret cannot flow into `{Self}`",
+    label = "`{Self}` is not MoreSecretThan `{T}`",
+    note = "a secret block can only unwrap secrets whose label is LessSecretThan the block's label; a `{T}` secret cannot be unwrapped in a `{Self}` secret block"
+)]
+// A marker trait, so Secrecy's subset impl below may overlap with the reflexive impl
+#[marker]
 pub trait MoreSecretThan<T>: Label {}
 
+// Inverse of MoreSecretThan, for writing "can flow into" bounds in the natural direction,
+// e.g., `where Src: LessSecretThan<Dst>`. Reflexive like MoreSecretThan.
+pub trait LessSecretThan<T>: Label {}
+impl<A: Label, B> LessSecretThan<B> for A where B: MoreSecretThan<A> {}
+
+// Labels whose secrets may be observed directly. Sealed, so only Label_Empty can implement it.
+pub trait IsPublic: Label + private::Sealed {}
+impl IsPublic for Label_Empty {}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for super::Label_Empty {}
+}
+
 // encode lattice relationships
 impl<T: Label> MoreSecretThan<T> for T {} // reflexive property
 
@@ -64,3 +92,131 @@ impl MoreSecretThan<Label_C> for Label_ABC {}
 impl MoreSecretThan<Label_AB> for Label_ABC {}
 impl MoreSecretThan<Label_BC> for Label_ABC {}
 impl MoreSecretThan<Label_AC> for Label_ABC {}
+
+impl MoreSecretThan<Label_Empty> for Label_Top {}
+impl MoreSecretThan<Label_A> for Label_Top {}
+impl MoreSecretThan<Label_B> for Label_Top {}
+impl MoreSecretThan<Label_C> for Label_Top {}
+impl MoreSecretThan<Label_AB> for Label_Top {}
+impl MoreSecretThan<Label_BC> for Label_Top {}
+impl MoreSecretThan<Label_AC> for Label_Top {}
+impl MoreSecretThan<Label_ABC> for Label_Top {}
+
+// Labels built from principals instead of one struct per combination, e.g., Secrecy<(Alice, Bob)>
+// plays the role of Label_AB. A label is more secret than every label whose principals are a subset
+// of its own, regardless of order or repeats, so Secrecy<(Alice,)> flows into Secrecy<(Bob, Alice)>.
+// Secrecy<()> is public, like Label_Empty. Sets hold up to 6 principals.
+pub trait Principal: 'static {}
+
+pub struct Secrecy<S: PrincipalSet> {
+    _principals: PhantomData<S>,
+}
+
+impl<S: PrincipalSet> Default for Secrecy<S> {
+    fn default() -> Self {
+        Secrecy { _principals: PhantomData }
+    }
+}
+
+impl<S: PrincipalSet> Clone for Secrecy<S> {
+    fn clone(&self) -> Self {
+        Secrecy::default()
+    }
+}
+
+// Secrecy holds no data, whatever the principals are
+unsafe impl<S: PrincipalSet> VisibleSideEffectFree for Secrecy<S> {}
+unsafe impl<S: PrincipalSet> Label for Secrecy<S> {}
+
+// A tuple of distinct or repeated principals
+pub trait PrincipalSet: 'static {}
+
+// The set contains principal P. A marker trait, since a tuple with repeats contains P twice.
+#[rustc_on_unimplemented(
+    message = "principal `{P}` is not in `{Self}`",
+    label = "a secret labeled with `{P}` cannot flow into a Secrecy label without it"
+)]
+#[marker]
+pub trait Contains<P> {}
+
+// Every principal in Self is in S
+#[marker]
+pub trait SubsetOf<S> {}
+
+macro_rules! principal_sets {
+    ($([$($p:ident)*])*) => ($(
+        impl<$($p: Principal),*> PrincipalSet for ($($p,)*) {}
+        impl<Super, $($p),*> SubsetOf<Super> for ($($p,)*) where $(Super: Contains<$p>),* {}
+        principal_sets!(@contains [$($p)*] $($p)*);
+    )*);
+    (@contains $all:tt $($p:ident)*) => ($(
+        principal_sets!(@contains_one $all $p);
+    )*);
+    (@contains_one [$($all:ident)*] $p:ident) => (
+        impl<$($all),*> Contains<$p> for ($($all,)*) {}
+    );
+}
+
+principal_sets! {
+    []
+    [P1]
+    [P1 P2]
+    [P1 P2 P3]
+    [P1 P2 P3 P4]
+    [P1 P2 P3 P4 P5]
+    [P1 P2 P3 P4 P5 P6]
+}
+
+impl<S: PrincipalSet, T: PrincipalSet> MoreSecretThan<Secrecy<T>> for Secrecy<S> where T: SubsetOf<S> {}
+impl<S: PrincipalSet> MoreSecretThan<Label_Empty> for Secrecy<S> {}
+impl MoreSecretThan<Secrecy<()>> for Label_Empty {}
+impl<S: PrincipalSet> MoreSecretThan<Secrecy<S>> for Label_Top {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Only compiles if L: MoreSecretThan<M>
+    fn _assert<L: MoreSecretThan<M>, M>() {}
+
+    // Asserts `high: MoreSecretThan<low>` for every pair, by subset inclusion of principals.
+    macro_rules! assert_flows {
+        ($($high:ty => [$($low:ty),*];)*) => {
+            $($(_assert::<$high, $low>();)*)*
+        };
+    }
+
+    #[test]
+    fn more_secret_than_is_closed() {
+        assert_flows! {
+            Label_Empty => [Label_Empty];
+            Label_A => [Label_Empty, Label_A];
+            Label_B => [Label_Empty, Label_B];
+            Label_C => [Label_Empty, Label_C];
+            Label_AB => [Label_Empty, Label_A, Label_B, Label_AB];
+            Label_BC => [Label_Empty, Label_B, Label_C, Label_BC];
+            Label_AC => [Label_Empty, Label_A, Label_C, Label_AC];
+            Label_ABC => [Label_Empty, Label_A, Label_B, Label_C, Label_AB, Label_BC, Label_AC, Label_ABC];
+            Label_Top => [Label_Empty, Label_A, Label_B, Label_C, Label_AB, Label_BC, Label_AC, Label_ABC, Label_Top];
+        }
+    }
+
+    struct Alice;
+    struct Bob;
+    struct Carol;
+    impl Principal for Alice {}
+    impl Principal for Bob {}
+    impl Principal for Carol {}
+
+    #[test]
+    fn secrecy_flows_by_subset() {
+        assert_flows! {
+            Secrecy<()> => [Label_Empty, Secrecy<()>];
+            Label_Empty => [Secrecy<()>];
+            Secrecy<(Alice,)> => [Label_Empty, Secrecy<()>, Secrecy<(Alice,)>, Secrecy<(Alice, Alice)>];
+            Secrecy<(Alice, Bob)> => [Secrecy<(Alice,)>, Secrecy<(Bob,)>, Secrecy<(Bob, Alice)>];
+            Secrecy<(Carol, Bob, Alice)> => [Secrecy<(Alice, Bob)>, Secrecy<(Alice, Carol)>, Secrecy<(Alice, Bob, Carol)>];
+            Label_Top => [Secrecy<(Alice, Bob, Carol)>];
+        }
+    }
+}
@@ -1,4 +1,10 @@
-#![feature(auto_traits, negative_impls, fn_traits, unboxed_closures, const_trait_impl, allocator_api, slice_index_methods)]
+#![feature(auto_traits, negative_impls, fn_traits, unboxed_closures, const_trait_impl, allocator_api, slice_index_methods, rustc_attrs, try_trait_v2, marker_trait_attr)]
 //#![feature(min_specialization)]
+// The "zeroize" feature picks Secret's drop behavior by whether the payload implements Zeroize
+#![cfg_attr(feature = "zeroize", feature(specialization))]
+#![cfg_attr(feature = "zeroize", allow(incomplete_features))]
+// secret_block! expands to ::secret_structs paths, which this lets the crate use itself
+extern crate self as secret_structs;
+
 pub mod secret;
 pub mod lattice;
\ No newline at end of file
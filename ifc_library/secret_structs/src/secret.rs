@@ -6,6 +6,10 @@ macro_rules! secret_block {
     ($t:ty $e:block) => {
         secret_macros::secret_block!($t $e)
     };
+    // A `ty` can't be followed by `#`, so blocks with attributes (e.g., #[arith = "checked"]) are passed on as is
+    ($($tokens:tt)*) => {
+        secret_macros::secret_block!($($tokens)*)
+    };
 }
 
 #[macro_export]
@@ -13,6 +17,30 @@ macro_rules! secret_block_no_return {
     ($t:ty $e:block) => {
         secret_macros::secret_block_no_return!($t $e)
     };
+    // A `ty` can't be followed by `#`, so blocks with attributes (e.g., #[arith = "checked"]) are passed on as is
+    ($($tokens:tt)*) => {
+        secret_macros::secret_block_no_return!($($tokens)*)
+    };
+}
+
+/** Statically asserts that each type is InvisibleSideEffectFree and has no Drop, Deref or DerefMut
+impl, the capabilities #[derive(InvisibleSideEffectFreeDerive)] rules out with negative impls.
+Meant for auditing hand-written `unsafe impl InvisibleSideEffectFree`s, e.g.,
+`assert_sealed!(Code, Occ);`. Each missing or forbidden capability is reported with the type's
+name. Only concrete types can be checked, since a generic type's impls aren't known. Pointer
+types such as Box implement Deref and Drop on purpose, so they fail. */
+#[macro_export]
+macro_rules! assert_sealed {
+    ($($t:ty),+ $(,)?) => {$(
+        const _: () = {
+            #[allow(unused_imports)]
+            use $crate::secret::sealed_probe::{Fallback as _, Probe};
+            let _ = $crate::secret::check_type_is_secret_block_safe::<$t>;
+            assert!(!Probe::<$t>::HAS_DROP, concat!("`", stringify!($t), "` has a Drop impl, which can have invisible side effects"));
+            assert!(!Probe::<$t>::HAS_DEREF, concat!("`", stringify!($t), "` implements Deref, which can have invisible side effects"));
+            assert!(!Probe::<$t>::HAS_DEREF_MUT, concat!("`", stringify!($t), "` implements DerefMut, which can have invisible side effects"));
+        };
+    )+};
 }
 
 use std::mem::ManuallyDrop;
@@ -26,6 +54,9 @@ use crate::lattice as lattice;
 use std::fmt;
 use std::{marker::PhantomData};
 
+/** Entry point of secret_block!: runs the (already checked) block closure, whose captures must be
+VisibleSideEffectFree and whose result must be a Secret (or tuple of Secrets) no less secret than L.
+with_label goes through here too, for closures that return a Vetted value instead of a Secret. */
 pub fn call_closure<L, F, R>(clos: F) -> R
 where
     F: FnOnce() -> R + VisibleSideEffectFree,
@@ -41,6 +72,113 @@ where
     clos()
 }
 
+/** Runs f and classifies its result at label L, without using secret_block!, through the same
+call_closure entry point as a secret block. The Vetted return value is the proof that f's result is
+side-effect free, e.g., because it comes from a #[side_effect_free_attr] function or was manually
+vetted with Vetted::wrap. */
+pub fn with_label<L, T>(f: impl FnOnce() -> Vetted<T> + VisibleSideEffectFree) -> Secret<T, L>
+where
+    L: lattice::Label,
+    T: SecretValueSafe + InvisibleSideEffectFree,
+{
+    call_closure::<L, _, _>(|| unsafe { Secret::<T, L>::new(f().unwrap()) })
+}
+
+/** Classifies a public value at label L, e.g., `where T: Classifiable<L>` in label-generic code.
+Labeling a public value can't leak anything, so this needs neither a secret block nor Vetted. */
+pub trait Classifiable<L: lattice::Label>: Sized + SecretValueSafe {
+    fn classify(self) -> Secret<Self, L>;
+}
+
+impl<T: SecretValueSafe, L: lattice::Label> Classifiable<L> for T {
+    fn classify(self) -> Secret<T, L> {
+        unsafe { Secret::<T, L>::new(self) }
+    }
+}
+
+/** Called by secret_assert!(cond) in debug builds when cond is false. Aborts instead of panicking,
+since a panic would be caught by the secret block, which would just return its default value. */
+pub fn secret_assert_failed(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    std::process::abort()
+}
+
+/** Folds items into init with f, e.g., to find the largest of several secrets. All values share
+label L, so the result keeps it. f returns Vetted; see with_label. */
+pub fn combine_vec<T, L, F>(items: &[Secret<T, L>], init: Secret<T, L>, mut f: F) -> Secret<T, L>
+where
+    L: lattice::Label,
+    T: SecretValueSafe + InvisibleSideEffectFree,
+    F: FnMut(&T, &T) -> Vetted<T> + VisibleSideEffectFree,
+{
+    let mut acc = init.unwrap();
+    for item in items {
+        acc = unsafe { f(&acc, &item.val).unwrap() };
+    }
+    unsafe { Secret::<T, L>::new(acc) }
+}
+
+/** Turns a vector of secrets into one secret vector at the same label, without declassifying. */
+pub fn transpose_in<T, L>(items: Vec<Secret<T, L>>) -> Secret<Vec<T>, L>
+where
+    L: lattice::Label,
+    T: InvisibleSideEffectFree + Immutable,
+{
+    let values: Vec<T> = items.into_iter().map(Secret::unwrap).collect();
+    unsafe { Secret::<Vec<T>, L>::new(values) }
+}
+
+/** Splits a secret array into one secret per element, without declassifying. The length N is
+part of the type, so it is public already; a secret Vec has no such form, since splitting it would
+make its length public. */
+pub fn transpose_out<T, L, const N: usize>(items: Secret<[T; N], L>) -> [Secret<T, L>; N]
+where
+    L: lattice::Label,
+    T: InvisibleSideEffectFree + Immutable,
+{
+    items.unwrap().map(|item| unsafe { Secret::<T, L>::new(item) })
+}
+
+/** Sorts the secret vector in ascending order with a bitonic sorting network. Which pairs get
+compared (and in what order) depends only on v's length, never on its contents, unlike
+<[T]>::sort inside a secret block, whose memory accesses reveal the permutation.
+
+The price is O(n log^2 n) comparisons instead of O(n log n), i.e., roughly log n times slower
+for large vectors. Only the schedule is oblivious: each compare-exchange still branches on the
+comparison, so this isn't constant-time. Incomparable values (e.g., NaN) are never swapped. */
+pub fn sort_secret<T, L>(v: &mut Secret<Vec<T>, L>)
+where
+    L: lattice::Label,
+    T: SafePartialOrd,
+    Vec<T>: SecretValueSafe,
+{
+    let n = v.val.len();
+    bitonic_sort(&mut v.val, 0, n, true);
+}
+
+fn bitonic_sort<T: SafePartialOrd>(v: &mut [T], lo: usize, n: usize, ascending: bool) {
+    if n > 1 {
+        let half = n / 2;
+        bitonic_sort(v, lo, half, !ascending);
+        bitonic_sort(v, lo + half, n - half, ascending);
+        bitonic_merge(v, lo, n, ascending);
+    }
+}
+
+// Merges a bitonic run of any length n, comparing against the largest power of two below n
+fn bitonic_merge<T: SafePartialOrd>(v: &mut [T], lo: usize, n: usize, ascending: bool) {
+    if n > 1 {
+        let m = n.next_power_of_two() / 2;
+        for i in lo..lo + n - m {
+            if v[i].safe_gt(&v[i + m]) == ascending && v[i + m].safe_gt(&v[i]) != ascending {
+                v.swap(i, i + m);
+            }
+        }
+        bitonic_merge(v, lo, m, ascending);
+        bitonic_merge(v, lo + m, n - m, ascending);
+    }
+}
+
 /** This trait helps allow secret closures to return tuples of Secrets. */
 pub unsafe trait SecretTrait<L> {}
 unsafe impl<T: SecretValueSafe, L: lattice::Label, L1: lattice::Label> SecretTrait<L> for Secret<T, L1> where
@@ -98,6 +236,21 @@ impl<T> Vetted<T> where T: InvisibleSideEffectFree {
     }
 }
 
+// Secret blocks are expanded into closures, so a missing impl inside a closure is (almost always)
+// a non-InvisibleSideEffectFree value captured by a secret block, e.g., through
+// make_check_secret_block_safe_ptr_read. Name the offending type in that case. Elsewhere (e.g.,
+// Secret::new), point at the usual culprits.
+#[rustc_on_unimplemented(
+    on(
+        ItemContext = "a closure",
+        message = "value of type `{Self}` captured by secret block is not InvisibleSideEffectFree",
+        label = "`{Self}` cannot be used in a secret block",
+        note = "implement InvisibleSideEffectFree for `{Self}` (e.g., with #[derive(InvisibleSideEffectFreeDerive)]) if it has no side effects"
+    ),
+    message = "`{Self}` is not InvisibleSideEffectFree",
+    label = "`{Self}` cannot be wrapped in a Secret or used in a secret block",
+    note = "types with a Drop impl or interior mutability can have invisible side effects"
+)]
 pub unsafe trait InvisibleSideEffectFree {
     // Limits what can be used in secret blocks
     unsafe fn check_all_types() {} // Overrided by #[derive(InvisibleSideEffectFree)]
@@ -105,6 +258,74 @@ pub unsafe trait InvisibleSideEffectFree {
 
 pub fn check_type_is_secret_block_safe<T: InvisibleSideEffectFree>() {}
 
+// Used by assert_sealed!. Probe<T>'s inherent consts only exist when T has the impl, and otherwise
+// the path falls back to Fallback's, so the answer is known at compile time for concrete types.
+#[doc(hidden)]
+pub mod sealed_probe {
+    use std::marker::PhantomData;
+
+    pub struct Probe<T: ?Sized>(PhantomData<T>);
+
+    pub trait Fallback {
+        const HAS_DROP: bool = false;
+        const HAS_DEREF: bool = false;
+        const HAS_DEREF_MUT: bool = false;
+    }
+    impl<T: ?Sized> Fallback for Probe<T> {}
+
+    #[allow(drop_bounds)]
+    impl<T: ?Sized + Drop> Probe<T> {
+        pub const HAS_DROP: bool = true;
+    }
+    impl<T: ?Sized + std::ops::Deref> Probe<T> {
+        pub const HAS_DEREF: bool = true;
+    }
+    impl<T: ?Sized + std::ops::DerefMut> Probe<T> {
+        pub const HAS_DEREF_MUT: bool = true;
+    }
+}
+
+/** Called for `T::default()` in secret blocks. The argument (the written `T::default`) only
+determines T, so an inherent `default` function that shadows the Default impl is never called. */
+pub fn trait_default<T: SafeDefault + InvisibleSideEffectFree>(_: fn() -> T) -> T {
+    T::default()
+}
+
+/** Called for `Default::default()` in secret blocks. */
+pub fn safe_default<T: SafeDefault + InvisibleSideEffectFree>() -> T {
+    T::default()
+}
+
+/** Types whose Default impl has no side effects, so secret blocks may call it.
+
+# Safety
+Only implement for types whose `default()` has no side effects, e.g., a `#[derive(Default)]` over
+fields that are all SafeDefault. */
+#[rustc_on_unimplemented(
+    message = "`{Self}`'s Default impl is not known to be side-effect free",
+    label = "`{Self}::default()` cannot be called in a secret block",
+    note = "implement SafeDefault for `{Self}` if its Default impl has no side effects"
+)]
+pub unsafe trait SafeDefault: Default {}
+
+macro_rules! safe_default_impl {
+    ($($t:ty)*) => ($(
+        unsafe impl SafeDefault for $t {}
+    )*)
+}
+
+safe_default_impl! { () bool char f32 f64 usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 String }
+
+unsafe impl<T: InvisibleSideEffectFree> SafeDefault for Vec<T> {}
+unsafe impl<T> SafeDefault for Option<T> {}
+unsafe impl<T: SafeDefault> SafeDefault for Box<T> {}
+unsafe impl<T: ?Sized> SafeDefault for PhantomData<T> {}
+unsafe impl<K, V> SafeDefault for std::collections::HashMap<K, V> {}
+unsafe impl<K> SafeDefault for std::collections::HashSet<K> {}
+unsafe impl<T: SafeDefault, U: SafeDefault> SafeDefault for (T, U) {}
+unsafe impl<T: SafeDefault, U: SafeDefault, V: SafeDefault> SafeDefault for (T, U, V) {}
+unsafe impl<T: SafeDefault, const N: usize> SafeDefault for [T; N] where [T; N]: Default {}
+
 pub fn check_ISEF<T: InvisibleSideEffectFree>(x: T) -> T {
     //std::ptr::read(x)
     x
@@ -134,10 +355,69 @@ pub fn check_safe_range_bounds<B: SafeRangeBounds>(b: B) -> B {
     b
 }
 
+// Usage: *check_safe_deref(e)
+// Checks that e is a reference or smart pointer. Raw pointers can read arbitrary memory,
+// so they may only be dereferenced inside unchecked_operation.
+pub fn check_safe_deref<P: SafeDeref>(p: P) -> P {
+    p
+}
+
+/** # Safety
+Only implement for pointer types whose Deref has no side effects. */
+#[rustc_on_unimplemented(
+    message = "`{Self}` cannot be dereferenced in a secret block",
+    label = "raw pointer dereference",
+    note = "dereferencing a raw pointer is only allowed inside unchecked_operation(...)"
+)]
+pub unsafe trait SafeDeref {}
+unsafe impl<T: ?Sized> SafeDeref for &T {}
+unsafe impl<T: ?Sized> SafeDeref for &mut T {}
+unsafe impl<T: ?Sized> SafeDeref for Box<T> {}
+unsafe impl<T> SafeDeref for Vec<T> {}
+unsafe impl SafeDeref for String {}
+unsafe impl<T: ?Sized> SafeDeref for std::mem::ManuallyDrop<T> {}
+unsafe impl<T: ?Sized> SafeDeref for std::rc::Rc<T> {}
+unsafe impl<T: ?Sized> SafeDeref for std::sync::Arc<T> {}
+
+/** The receiver of an allowlisted `str` method called with method syntax in a secret block, e.g.,
+`s.trim()`. Borrowing as &str through this trait instead of a deref coercion keeps a user type's
+Deref impl from running.
+
+# Safety
+Only implement for types whose conversion to &str has no side effects. */
+#[rustc_on_unimplemented(
+    message = "`{Self}` is not a string",
+    label = "allowlisted `str` methods can only be called on String or str in a secret block"
+)]
+pub unsafe trait SafeAsStr {
+    fn safe_as_str(&self) -> &str;
+}
+unsafe impl SafeAsStr for str {
+    fn safe_as_str(&self) -> &str {
+        self
+    }
+}
+unsafe impl SafeAsStr for String {
+    fn safe_as_str(&self) -> &str {
+        self.as_str()
+    }
+}
+unsafe impl<T: SafeAsStr + ?Sized> SafeAsStr for &T {
+    fn safe_as_str(&self) -> &str {
+        (**self).safe_as_str()
+    }
+}
+unsafe impl<T: SafeAsStr + ?Sized> SafeAsStr for &mut T {
+    fn safe_as_str(&self) -> &str {
+        (**self).safe_as_str()
+    }
+}
+
 unsafe impl InvisibleSideEffectFree for () {}
-unsafe impl<T: SecretValueSafe, L: lattice::Label> InvisibleSideEffectFree for Secret<T, L> {}
+unsafe impl<T: SecretValueSafe + ?Sized, L: lattice::Label> InvisibleSideEffectFree for Secret<T, L> {}
 unsafe impl<T: InvisibleSideEffectFree, U: InvisibleSideEffectFree> InvisibleSideEffectFree for (T, U) {}
 unsafe impl<T: InvisibleSideEffectFree, U: InvisibleSideEffectFree, V: InvisibleSideEffectFree> InvisibleSideEffectFree for (T, U, V) {}
+// Box's Drop only drops the (ISEF) contents and frees the allocation, which isn't observable
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for Box<T> {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for Vec<T> {}
 unsafe impl InvisibleSideEffectFree for f32 {}
@@ -156,18 +436,18 @@ unsafe impl InvisibleSideEffectFree for u128 {}
 unsafe impl InvisibleSideEffectFree for usize {}
 unsafe impl InvisibleSideEffectFree for String {}
 unsafe impl InvisibleSideEffectFree for str {}
-unsafe impl InvisibleSideEffectFree for &str {}
 unsafe impl InvisibleSideEffectFree for std::str::Chars<'_> {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for *mut T {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for Option<T> {}
-unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for &T {}
-unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for &mut T {}
+unsafe impl<T: InvisibleSideEffectFree, E: InvisibleSideEffectFree> InvisibleSideEffectFree for Result<T, E> {}
+unsafe impl<T: InvisibleSideEffectFree + ?Sized> InvisibleSideEffectFree for &T {}
+unsafe impl<T: InvisibleSideEffectFree + ?Sized> InvisibleSideEffectFree for &mut T {}
 unsafe impl InvisibleSideEffectFree for char {}
 unsafe impl InvisibleSideEffectFree for bool {}
+unsafe impl InvisibleSideEffectFree for std::cmp::Ordering {}
+unsafe impl InvisibleSideEffectFree for std::num::ParseIntError {}
 unsafe impl InvisibleSideEffectFree for PathBuf {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for [T] {}
-unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for &[T] {}
-unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for &mut [T] {}
 unsafe impl<T: InvisibleSideEffectFree, const N: usize> InvisibleSideEffectFree for [T; N] {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for *const T {}
 #[cfg(target_arch = "x86_64")]
@@ -175,10 +455,23 @@ unsafe impl InvisibleSideEffectFree for std::arch::x86_64::__m256d {}
 #[cfg(target_arch = "x86_64")]
 unsafe impl InvisibleSideEffectFree for std::arch::x86_64::__m128 {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::Copied<T> {}
+unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::Cloned<T> {}
+unsafe impl<T: InvisibleSideEffectFree, const N: usize> InvisibleSideEffectFree for std::array::IntoIter<T, N> {}
+unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::vec::IntoIter<T> {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::Cycle<T> {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::Take<T> {}
+unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::Rev<T> {}
+unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::Skip<T> {}
+unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::StepBy<T> {}
+unsafe impl<A: InvisibleSideEffectFree, B: InvisibleSideEffectFree> InvisibleSideEffectFree for std::iter::Chain<A, B> {}
+unsafe impl<T: InvisibleSideEffectFree + Iterator> InvisibleSideEffectFree for std::iter::Flatten<T>
+    where T::Item: IntoIterator, <T::Item as IntoIterator>::IntoIter: InvisibleSideEffectFree, {}
 unsafe impl<'a, T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::slice::Iter<'a, T> {}
+unsafe impl<'a, T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::slice::Chunks<'a, T> {}
+unsafe impl<'a, T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::slice::ChunksExact<'a, T> {}
+unsafe impl<'a, T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::slice::Windows<'a, T> {}
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::mem::MaybeUninit<T> {}
+unsafe impl<T: ?Sized> InvisibleSideEffectFree for PhantomData<T> {} // Holds no data
 unsafe impl<T: InvisibleSideEffectFree> InvisibleSideEffectFree for std::ops::Range<T> {}
 unsafe impl<K: InvisibleSideEffectFree, V: InvisibleSideEffectFree> InvisibleSideEffectFree for std::collections::HashMap<K, V>  {}
 unsafe impl<K: InvisibleSideEffectFree> InvisibleSideEffectFree for std::collections::HashSet<K>  {}
@@ -205,16 +498,24 @@ impl<T: Base> Base for &T {}
  * Struct wrapper containing a secret of type T with secrecy level L
  * Note: PhantomData<L> is just to fix issue that L is otherwise unused.
  * This may not be the best solution.   
+ * T may be unsized (e.g., Secret<[u8], L> or Secret<str, L>) when used behind a reference,
+ * so val must stay the last field.
+ *
+ * Getting at the value outside a secret block:
+ * - declassify, declassify_ref, declassify_ref_mut: any label. These are the declassifications
+ *   that the "stats" feature counts. declassify_to lowers the label only part of the way.
+ * - get_value_ref, get_value_consume: public labels only (lattice::IsPublic), so using them on a
+ *   secret is a compile error instead of a silent declassification.
  */
 #[derive(Clone, Default)]
 pub struct Secret<T, L /*,D*/>
 where
-    T: SecretValueSafe,
+    T: SecretValueSafe + ?Sized,
     L: lattice::Label
 {
-    val: ManuallyDrop<T>,
     _pd: PhantomData<L>,
     /*dynamic: D*/
+    val: ManuallyDrop<T>,
 }
 
 //impl<T: SecretValueSafe,L: lattice::Label> UnwindSafe for Secret<T,L> {}
@@ -227,7 +528,7 @@ impl<T: ?Sized> !Immutable for &mut T {}
 //impl<T: ?Sized> !UniquePtr for std::sync::Arc<T> {}
 
 pub unsafe trait SecretValueSafe {} // For limiting what values can be wrapped in a Secret
-unsafe impl<T> SecretValueSafe for T where T: Immutable + InvisibleSideEffectFree {}
+unsafe impl<T: ?Sized> SecretValueSafe for T where T: Immutable + InvisibleSideEffectFree {}
 
 /*
  * Code to restrict using unary, binary operators in secret closures to only primitive types.
@@ -532,6 +833,116 @@ macro_rules! div_assign_impl_float {
 }
 div_assign_impl_float! { f32 f64 }
 
+/* Checked arithmetic, used instead of +, -, *, / by secret_block!(L #[arith = "checked"] { ... }).
+Integer overflow (and division by zero) always panics, whatever the profile's overflow-checks setting;
+the secret block then returns its default value as for any other panic. Floats behave as usual. */
+/** # Safety
+Implementations must be side-effect free, as for SafeAdd. */
+pub unsafe trait SafeCheckedAdd<Rhs = Self> {
+    type Output;
+    fn safe_checked_add(self, rhs: Rhs) -> Self::Output;
+}
+/** # Safety
+Implementations must be side-effect free, as for SafeSub. */
+pub unsafe trait SafeCheckedSub<Rhs = Self> {
+    type Output;
+    fn safe_checked_sub(self, rhs: Rhs) -> Self::Output;
+}
+/** # Safety
+Implementations must be side-effect free, as for SafeMul. */
+pub unsafe trait SafeCheckedMul<Rhs = Self> {
+    type Output;
+    fn safe_checked_mul(self, rhs: Rhs) -> Self::Output;
+}
+/** # Safety
+Implementations must be side-effect free, as for SafeDiv. */
+pub unsafe trait SafeCheckedDiv<Rhs = Self> {
+    type Output;
+    fn safe_checked_div(self, rhs: Rhs) -> Self::Output;
+}
+
+macro_rules! checked_impl_integer {
+    ($imp:ident, $method:ident, $checked:ident, $msg:expr; $($t:ty)*) => ($(
+        unsafe impl $imp for $t {
+            type Output = $t;
+            #[inline]
+            fn $method(self, other: $t) -> $t {
+                match self.$checked(other) {
+                    Some(result) => result,
+                    None => panic!($msg),
+                }
+            }
+        }
+        unsafe_forward_ref_binop! { unsafe impl $imp, $method for $t, $t }
+    )*)
+}
+
+macro_rules! checked_impl_float {
+    ($imp:ident, $method:ident, $unchecked:ident, $unchecked_method:ident; $($t:ty)*) => ($(
+        unsafe impl $imp for $t {
+            type Output = $t;
+            #[inline]
+            fn $method(self, other: $t) -> $t { $unchecked::$unchecked_method(self, other) }
+        }
+        unsafe_forward_ref_binop! { unsafe impl $imp, $method for $t, $t }
+    )*)
+}
+
+checked_impl_integer! { SafeCheckedAdd, safe_checked_add, checked_add, "attempt to add with overflow"; usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
+checked_impl_integer! { SafeCheckedSub, safe_checked_sub, checked_sub, "attempt to subtract with overflow"; usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
+checked_impl_integer! { SafeCheckedMul, safe_checked_mul, checked_mul, "attempt to multiply with overflow"; usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
+checked_impl_integer! { SafeCheckedDiv, safe_checked_div, checked_div, "attempt to divide by zero or with overflow"; usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
+checked_impl_float! { SafeCheckedAdd, safe_checked_add, SafeAdd, safe_add; f32 f64 }
+checked_impl_float! { SafeCheckedSub, safe_checked_sub, SafeSub, safe_sub; f32 f64 }
+checked_impl_float! { SafeCheckedMul, safe_checked_mul, SafeMul, safe_mul; f32 f64 }
+checked_impl_float! { SafeCheckedDiv, safe_checked_div, SafeDiv, safe_div; f32 f64 }
+
+// String concatenation can't overflow
+unsafe impl SafeCheckedAdd<&str> for String {
+    type Output = String;
+
+    #[inline]
+    fn safe_checked_add(self, other: &str) -> Self::Output {
+        self + other
+    }
+}
+
+/* Fallible conversions, used by Secret::narrow. Only the std integer conversions are marked,
+since a user-written TryFrom could leak the value through a side effect. */
+/** # Safety
+The TryFrom implementation must be side-effect free. */
+pub unsafe trait SafeTryFrom<T>: std::convert::TryFrom<T> {}
+
+macro_rules! safe_try_from_impl {
+    ($($t:ty)*) => { safe_try_from_impl! { @each [$($t)*] $($t)* } };
+    (@each $all:tt $($u:ty)*) => { $( safe_try_from_impl! { @row $u; $all } )* };
+    (@row $u:ty; [$($t:ty)*]) => { $( unsafe impl SafeTryFrom<$t> for $u {} )* };
+}
+
+safe_try_from_impl! { usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
+
+/* Lossless conversions, used by Secret::widen. Only std's From impls between primitives are
+marked, for the same reason as SafeTryFrom. */
+/** # Safety
+The From implementation must be side-effect free. */
+pub unsafe trait SafeCast<T>: From<T> {}
+
+macro_rules! safe_cast_impl {
+    ($($t:ty => $($u:ty)*;)*) => { $( $( unsafe impl SafeCast<$t> for $u {} )* )* };
+}
+
+safe_cast_impl! {
+    u8 => u16 u32 u64 u128 usize i16 i32 i64 i128 isize;
+    u16 => u32 u64 u128 usize i32 i64 i128;
+    u32 => u64 u128 i64 i128;
+    u64 => u128 i128;
+    i8 => i16 i32 i64 i128 isize;
+    i16 => i32 i64 i128 isize;
+    i32 => i64 i128;
+    i64 => i128;
+    f32 => f64;
+}
+
 /* PartialEq */
 pub unsafe trait SafePartialEq<Rhs: ?Sized = Self> {
     /// This method tests for `self` and `other` values to be equal, and is used
@@ -564,6 +975,7 @@ macro_rules! parteq_impl {
 }
 
 parteq_impl! { bool char f32 f64 i8 i16 i32 i64 i128 isize str u8 u16 u32 u64 u128 usize }
+parteq_impl! { std::cmp::Ordering }
 
 pub unsafe fn safe_max_by<T, F: FnOnce(&T, &T) -> std::cmp::Ordering>(
     v1: T,
@@ -587,6 +999,10 @@ pub unsafe fn safe_min_by<T, F: FnOnce(&T, &T) -> std::cmp::Ordering>(
     }
 }
 
+#[rustc_on_unimplemented(
+    message = "`{Self}`'s comparison is not known to be side-effect free",
+    label = "only built-in types can be compared with max, min or clamp in a secret block"
+)]
 pub unsafe trait SafeOrd: Eq + PartialOrd<Self> {
     #[must_use]
     fn safe_cmp(&self, other: &Self) -> std::cmp::Ordering;
@@ -625,6 +1041,22 @@ pub unsafe trait SafeOrd: Eq + PartialOrd<Self> {
     }
 }
 
+/** Called for `std::cmp::max` and `Ord::max` in secret blocks. Unlike a user Ord impl, a SafeOrd
+comparison has no side effects. */
+pub fn ord_max<T: SafeOrd>(a: T, b: T) -> T {
+    unsafe { a.safe_max(b) }
+}
+
+/** Called for `std::cmp::min` and `Ord::min` in secret blocks. */
+pub fn ord_min<T: SafeOrd>(a: T, b: T) -> T {
+    unsafe { a.safe_min(b) }
+}
+
+/** Called for `Ord::clamp` in secret blocks. */
+pub fn ord_clamp<T: SafeOrd>(v: T, min: T, max: T) -> T {
+    v.safe_clamp(min, max)
+}
+
 unsafe impl SafeOrd for std::cmp::Ordering {
     #[inline]
     fn safe_cmp(&self, other: &std::cmp::Ordering) -> std::cmp::Ordering {
@@ -781,7 +1213,6 @@ not_impl! {bool usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128}
 /* Traits for all other overloadable operators (not implemented = disallowed by macro)
 TODO: implement these */
 pub unsafe trait SafeBitAndAssign {}
-pub unsafe trait SafeBitOr {}
 pub unsafe trait SafeBitOrAssign {}
 pub unsafe trait SafeBitXorAssign {}
 pub unsafe trait SafeDrop {}
@@ -810,6 +1241,17 @@ macro_rules! bitxor_impl {
 
 bitxor_impl! { bool usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
 
+// Element-wise, e.g., for XOR-ing byte-array keys with Secret::xor_mask
+unsafe impl<T: SafeBitXor<Output = T>, const N: usize> SafeBitXor for [T; N] {
+    type Output = [T; N];
+
+    #[inline]
+    fn safe_bitxor(self, other: [T; N]) -> [T; N] {
+        let mut other = IntoIterator::into_iter(other);
+        self.map(|x| x.safe_bitxor(other.next().unwrap()))
+    }
+}
+
 pub unsafe trait SafeBitAnd<Rhs = Self> {
     /// The resulting type after applying the `&` operator.
     type Output;
@@ -831,6 +1273,27 @@ macro_rules! bitand_impl {
 
 bitand_impl! { bool usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
 
+pub unsafe trait SafeBitOr<Rhs = Self> {
+    /// The resulting type after applying the `|` operator.
+    type Output;
+    fn safe_bitor(self, rhs: Rhs) -> Self::Output;
+}
+
+macro_rules! bitor_impl {
+    ($($t:ty)*) => ($(
+        unsafe impl SafeBitOr for $t {
+            type Output = $t;
+
+            #[inline]
+            fn safe_bitor(self, rhs: $t) -> $t { self | rhs }
+        }
+
+        unsafe_forward_ref_binop! { unsafe impl SafeBitOr, safe_bitor for $t, $t }
+    )*)
+}
+
+bitor_impl! { bool usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 }
+
 pub unsafe trait SafeIndex {}
 unsafe impl SafeIndex for std::ops::Range<usize> {}
 unsafe impl SafeIndex for std::ops::RangeFrom<usize> {}
@@ -884,6 +1347,33 @@ unsafe impl<T, const N: usize> SafeIndexExpr for [T; N] where [T]: SafeIndexExpr
 unsafe impl<T, const N: usize> SafeIndexExpr for &[T; N] where [T]: SafeIndexExpr, {}
 unsafe impl<T, const N: usize> SafeIndexExpr for &mut [T; N] where [T]: SafeIndexExpr, {}
 
+/** Mutable indexing for assignments to `e[i]` in secret blocks. The checking code calls it with
+method syntax, so a `&mut` base is reborrowed rather than moved.
+
+# Safety
+safe_index_mut must only index the container, with no other side effects. */
+pub unsafe trait SafeIndexMut<I> {
+    type Output: ?Sized;
+    fn safe_index_mut(&mut self, index: I) -> &mut Self::Output;
+}
+unsafe impl<T, I> SafeIndexMut<I> for [T] where I: SafeIndex + std::slice::SliceIndex<[T]>, {
+    type Output = I::Output;
+    fn safe_index_mut(&mut self, index: I) -> &mut I::Output { &mut self[index] }
+}
+unsafe impl<T, I, const N: usize> SafeIndexMut<I> for [T; N] where I: SafeIndex + std::slice::SliceIndex<[T]>, {
+    type Output = I::Output;
+    fn safe_index_mut(&mut self, index: I) -> &mut I::Output { &mut self[index] }
+}
+unsafe impl<T, I, A> SafeIndexMut<I> for std::vec::Vec<T, A>
+    where I: SafeIndex + std::slice::SliceIndex<[T]>, A: std::alloc::Allocator, {
+    type Output = I::Output;
+    fn safe_index_mut(&mut self, index: I) -> &mut I::Output { &mut self[index] }
+}
+unsafe impl<T, A> SafeIndexMut<usize> for std::collections::VecDeque<T, A> where A: std::alloc::Allocator, {
+    type Output = T;
+    fn safe_index_mut(&mut self, index: usize) -> &mut T { &mut self[index] }
+}
+
 pub unsafe trait SafeRangeBounds {}
 
 // only allow ranges to be of Rust numeric types
@@ -909,6 +1399,195 @@ unsafe impl<T: SafeRangeTypes> SafeRangeBounds for std::ops::RangeTo<T> {}
 // unsafe impl<T: SafeRangeTypes> SafeRangeBounds for std::ops::RangeToInclusive<&T> {}
 unsafe impl<T: SafeRangeTypes> SafeRangeBounds for std::ops::RangeToInclusive<T> {}
 
+/** Iterator chains in method-call form, e.g., `(0..n).map(|i| i * i).collect::<Vec<_>>()`.
+secret_block! routes `.map(..)`, `.collect()`, `.cloned()` and `.copied()`, and the consumers `.sum()`,
+`.product()`, `.max()`, `.min()`, `.count()`, `.last()`, `.position(..)`, `.find(..)`, `.any(..)` and
+`.all(..)`, to these methods instead of requiring a side_effect_free_attr method. Closures written in
+the block are body-checked as usual, and any other callable passed must pass the usual capture checks.
+
+position, find, any and all stop at the first match, so how long they take depends on the items.
+Everything a secret block iterates over is at the block's label, so the result is labeled the same;
+like branching on a secret, though, the timing is not protected.
+
+# Safety
+Only implement for iterators whose next() has no side effects of its own. */
+pub unsafe trait SafeIterator: Iterator {
+    fn safe_map<B, F>(self, f: F) -> std::iter::Map<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> B,
+    {
+        self.map(f)
+    }
+
+    fn safe_collect<C>(self) -> C
+    where
+        Self: Sized,
+        C: SafeFromIterator<Self::Item>,
+    {
+        self.collect()
+    }
+
+    // Clone is trusted here as for the allowlisted Clone::clone
+    fn safe_cloned<'a, T>(self) -> std::iter::Cloned<Self>
+    where
+        Self: Sized + Iterator<Item = &'a T>,
+        T: 'a + Clone + InvisibleSideEffectFree,
+    {
+        self.cloned()
+    }
+
+    fn safe_copied<'a, T>(self) -> std::iter::Copied<Self>
+    where
+        Self: Sized + Iterator<Item = &'a T>,
+        T: 'a + Copy + InvisibleSideEffectFree,
+    {
+        self.copied()
+    }
+
+    // Only the built-in numeric Sum and Product impls are trusted
+    fn safe_sum<S>(self) -> S
+    where
+        Self: Sized,
+        S: SafeRangeTypes + std::iter::Sum<Self::Item>,
+    {
+        self.sum()
+    }
+
+    fn safe_product<P>(self) -> P
+    where
+        Self: Sized,
+        P: SafeRangeTypes + std::iter::Product<Self::Item>,
+    {
+        self.product()
+    }
+
+    fn safe_max(self) -> Option<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: SafeOrd + Ord,
+    {
+        self.max()
+    }
+
+    fn safe_min(self) -> Option<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: SafeOrd + Ord,
+    {
+        self.min()
+    }
+
+    fn safe_count(self) -> usize
+    where
+        Self: Sized,
+    {
+        self.count()
+    }
+
+    fn safe_last(self) -> Option<Self::Item>
+    where
+        Self: Sized,
+    {
+        self.last()
+    }
+
+    fn safe_position<P>(&mut self, predicate: P) -> Option<usize>
+    where
+        Self: Sized,
+        P: FnMut(Self::Item) -> bool,
+    {
+        self.position(predicate)
+    }
+
+    fn safe_find<P>(&mut self, predicate: P) -> Option<Self::Item>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        self.find(predicate)
+    }
+
+    fn safe_any<F>(&mut self, f: F) -> bool
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> bool,
+    {
+        self.any(f)
+    }
+
+    fn safe_all<F>(&mut self, f: F) -> bool
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> bool,
+    {
+        self.all(f)
+    }
+}
+unsafe impl<T: SafeRangeTypes> SafeIterator for std::ops::Range<T> where std::ops::Range<T>: Iterator {}
+unsafe impl<T: SafeRangeTypes> SafeIterator for std::ops::RangeInclusive<T> where std::ops::RangeInclusive<T>: Iterator {}
+unsafe impl<'a, T: InvisibleSideEffectFree> SafeIterator for std::slice::Iter<'a, T> {}
+unsafe impl<B, I: SafeIterator, F: FnMut(I::Item) -> B> SafeIterator for std::iter::Map<I, F> {}
+unsafe impl<I: SafeIterator + DoubleEndedIterator> SafeIterator for std::iter::Rev<I> {}
+unsafe impl<I: SafeIterator> SafeIterator for std::iter::Skip<I> {}
+unsafe impl<I: SafeIterator> SafeIterator for std::iter::StepBy<I> {}
+unsafe impl<I: SafeIterator> SafeIterator for std::iter::Take<I> {}
+unsafe impl<A: SafeIterator, B: SafeIterator<Item = A::Item>> SafeIterator for std::iter::Chain<A, B> {}
+unsafe impl<I: SafeIterator> SafeIterator for std::iter::Flatten<I>
+    where I::Item: IntoIterator, <I::Item as IntoIterator>::IntoIter: SafeIterator, {}
+unsafe impl<'a, T: 'a + Clone + InvisibleSideEffectFree, I: SafeIterator<Item = &'a T>> SafeIterator for std::iter::Cloned<I> {}
+unsafe impl<'a, T: 'a + Copy + InvisibleSideEffectFree, I: SafeIterator<Item = &'a T>> SafeIterator for std::iter::Copied<I> {}
+unsafe impl<T: InvisibleSideEffectFree, const N: usize> SafeIterator for std::array::IntoIter<T, N> {}
+unsafe impl<T: InvisibleSideEffectFree> SafeIterator for std::vec::IntoIter<T> {}
+
+/** `.iter()` on arrays, slices and Vecs in secret blocks. secret_block! calls it with method
+syntax, so the receiver is auto-dereferenced as usual.
+
+# Safety
+Must only return the slice's own iterator. */
+pub unsafe trait SafeIter<T> {
+    fn safe_iter(&self) -> std::slice::Iter<'_, T>;
+}
+unsafe impl<T: InvisibleSideEffectFree> SafeIter<T> for [T] {
+    fn safe_iter(&self) -> std::slice::Iter<'_, T> {
+        self.iter()
+    }
+}
+
+/** `.into_iter()` on arrays, Vecs and references to them in secret blocks, also called with method syntax.
+
+# Safety
+Only implement for collections whose IntoIterator impl has no side effects and whose iterator is a SafeIterator. */
+pub unsafe trait SafeIntoIterator: IntoIterator
+where
+    Self::IntoIter: SafeIterator,
+{
+    fn safe_into_iter(self) -> Self::IntoIter
+    where
+        Self: Sized,
+    {
+        self.into_iter()
+    }
+}
+unsafe impl<T: InvisibleSideEffectFree, const N: usize> SafeIntoIterator for [T; N] {}
+unsafe impl<T: InvisibleSideEffectFree> SafeIntoIterator for Vec<T> {}
+unsafe impl<'a, T: InvisibleSideEffectFree> SafeIntoIterator for &'a [T] {}
+unsafe impl<'a, T: InvisibleSideEffectFree, const N: usize> SafeIntoIterator for &'a [T; N] {}
+unsafe impl<'a, T: InvisibleSideEffectFree> SafeIntoIterator for &'a Vec<T> {}
+
+/** Collections of InvisibleSideEffectFree items that safe_collect can build.
+
+# Safety
+Only implement for collections whose FromIterator impl has no side effects. HashMap, like the
+allowlisted HashMap::insert, relies on the key type's Hash and Eq impls. */
+pub unsafe trait SafeFromIterator<A>: std::iter::FromIterator<A> {}
+unsafe impl<T: InvisibleSideEffectFree> SafeFromIterator<T> for Vec<T> {}
+unsafe impl SafeFromIterator<char> for String {}
+unsafe impl<'a> SafeFromIterator<&'a str> for String {}
+unsafe impl SafeFromIterator<String> for String {}
+unsafe impl<K, V> SafeFromIterator<(K, V)> for std::collections::HashMap<K, V>
+    where K: InvisibleSideEffectFree + std::cmp::Eq + std::hash::Hash, V: InvisibleSideEffectFree, {}
+
 pub unsafe trait SafeRem {}
 pub unsafe trait SafeRemAssign {}
 pub unsafe trait SafeShl {}
@@ -916,10 +1595,41 @@ pub unsafe trait SafeShlAssign {}
 pub unsafe trait SafeShr {}
 pub unsafe trait SafeShrAssign {}
 
+/** Permission to reclassify secrets from label From to label To, for Secret::reclassify. It has no
+safe constructor, so every place that hands one out is an `unsafe` block, which keeps downgrades
+in one greppable policy module instead of scattered declassify calls. */
+pub struct DeclassifyCapability<From: lattice::Label, To: lattice::Label> {
+    _pd: PhantomData<(From, To)>,
+}
+
+impl<From: lattice::Label, To: lattice::Label> DeclassifyCapability<From, To> {
+    /** # Safety
+    Only grant the capability where the program's policy allows From data to be seen at To. */
+    pub unsafe fn new() -> Self {
+        DeclassifyCapability { _pd: PhantomData }
+    }
+}
+
+/** A runtime decision at a trust boundary, e.g., a config flag or the caller's role, consulted by
+Secret::try_into_public. It sees only the label's type name (e.g.,
+"secret_structs::lattice::Label_A"), never the value, so whether it allows a declassification
+can't depend on the secret. Closures taking the label name are policies too. */
+pub trait DeclassifyPolicy {
+    fn allows(&self, label: &'static str) -> bool;
+}
+
+impl<F: Fn(&'static str) -> bool> DeclassifyPolicy for F {
+    fn allows(&self, label: &'static str) -> bool {
+        self(label)
+    }
+}
+
 impl<T, L: lattice::Label> Secret<T, L>
 where
     T: SecretValueSafe,
 {
+    // SecretValueSafe requires InvisibleSideEffectFree, so a Drop-having or interior-mutable T is
+    // rejected here, at the call site, with InvisibleSideEffectFree's message.
     pub unsafe fn new(val: T) -> Secret<T, L> {
         Secret::<T, L> {
             val: ManuallyDrop::new(val),
@@ -937,39 +1647,503 @@ where
         Secret::<T,lattice::Label_Empty>::new(self.val)
     }*/
 
-    // Returning the interior value here, since it's not possible to return a reference to a new Secret (?)
-    pub fn declassify_ref(&self) -> &T {
+    pub fn declassify(self) -> Secret<T, lattice::Label_Empty> {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
         // returning an i64 would be another option
-        &self.val
-        //&Secret::<T,lattice::Label_Empty>::new(self.val)
+        unsafe { Secret::<T, lattice::Label_Empty>::new(self.unwrap()) }
     }
 
-    pub fn declassify_ref_mut(&mut self) -> &mut T {
-        &mut self.val
+    /** Declassifies to the plain value if policy allows it, and otherwise hands self back
+    untouched, so a denied crossing at a trust boundary keeps the secret labeled. Counted like
+    declassify when allowed. */
+    pub fn try_into_public(self, policy: &dyn DeclassifyPolicy) -> Result<T, Secret<T, L>> {
+        if !policy.allows(std::any::type_name::<L>()) {
+            return Err(self);
+        }
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        Ok(self.unwrap())
     }
 
-    pub fn declassify(self) -> Secret<T, lattice::Label_Empty> {
-        // returning an i64 would be another option
-        unsafe { Secret::<T, lattice::Label_Empty>::new(ManuallyDrop::into_inner(self.val)) }
+    /** Declassifies part of the way, to a label M below L, e.g., from Label_AB to Label_A. */
+    pub fn declassify_to<M: lattice::Label>(self) -> Secret<T, M>
+    where
+        L: lattice::MoreSecretThan<M>,
+    {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        unsafe { Secret::<T, M>::new(self.unwrap()) }
     }
 
+    #[deprecated(note = "use declassify_to::<M>() instead")]
     pub fn declassify_to_consume<M: lattice::Label>(self, _level: PhantomData<M>) -> Secret<T, M>
     where
         L: lattice::MoreSecretThan<M>,
     {
-        unsafe { Secret::<T, M>::new(ManuallyDrop::into_inner(self.val)) }
+        self.declassify_to()
     }
 
-    pub fn clone(&self) -> Secret<T, L>
+    /** Relabels self to L2 with the policy's permission, e.g., from Label_AB down to Label_A.
+    Counted like declassify. */
+    pub fn reclassify<L2: lattice::Label>(self, _token: &DeclassifyCapability<L, L2>) -> Secret<T, L2> {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        unsafe { Secret::<T, L2>::new(self.unwrap()) }
+    }
+
+    /** Declassifies only a summary of the value (e.g., a bucketed or noised count) computed by f,
+    never the value itself. Unlike project or fold_into, the result is public. f returns Vetted;
+    see with_label. Counted like declassify. */
+    pub fn declassify_via<U, F>(self, f: F) -> U
     where
-        T: Clone,
+        U: InvisibleSideEffectFree,
+        F: FnOnce(T) -> Vetted<U> + VisibleSideEffectFree,
     {
-        Secret::<T, L> {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        unsafe { f(self.unwrap()).unwrap() }
+    }
+
+    /** Relabels self to a more-secret label M without touching the value.
+    Raising secrecy is always safe, so no secret block is needed. */
+    pub fn cast_label<M: lattice::Label>(self) -> Secret<T, M>
+    where
+        M: lattice::MoreSecretThan<L>,
+    {
+        unsafe { Secret::<T, M>::new(self.unwrap()) }
+    }
+
+    /** Stores value in self and returns the old secret, like std::mem::replace.
+    Both secrets have the same label, so nothing is declassified. */
+    pub fn replace(&mut self, value: Secret<T, L>) -> Secret<T, L> {
+        std::mem::replace(self, value)
+    }
+
+    /** Takes the secret out of self, leaving a secret holding T::default(), like std::mem::take. */
+    pub fn take(&mut self) -> Secret<T, L>
+    where
+        T: Default,
+    {
+        self.replace(unsafe { Secret::<T, L>::new(T::default()) })
+    }
+
+    /** Folds public items into the secret value, e.g., counting matches while iterating a public
+    collection, without a secret block per item. f returns Vetted; see with_label. */
+    pub fn fold_into<I, F>(self, iter: I, mut f: F) -> Secret<T, L>
+    where
+        T: InvisibleSideEffectFree,
+        I: IntoIterator,
+        F: FnMut(T, I::Item) -> Vetted<T> + VisibleSideEffectFree,
+    {
+        let mut acc = self.unwrap();
+        for item in iter {
+            acc = unsafe { f(acc, item).unwrap() };
+        }
+        unsafe { Secret::<T, L>::new(acc) }
+    }
+
+    /** Converts the value losslessly with From (e.g., u8 to u64), keeping label L. For lossy
+    conversions, use narrow. */
+    pub fn widen<U>(self) -> Secret<U, L>
+    where
+        U: SafeCast<T> + InvisibleSideEffectFree + Immutable,
+    {
+        unsafe { Secret::<U, L>::new(<U as From<T>>::from(self.unwrap())) }
+    }
+
+    /** Converts the value with TryFrom (e.g., i64 to i32). Whether the conversion failed is itself
+    kept secret at label L, so an overflow reveals nothing. */
+    pub fn narrow<U>(self) -> Secret<Result<U, ()>, L>
+    where
+        U: SafeTryFrom<T> + InvisibleSideEffectFree + Immutable,
+    {
+        unsafe { Secret::<Result<U, ()>, L>::new(<U as std::convert::TryFrom<T>>::try_from(self.unwrap()).map_err(|_| ())) }
+    }
+
+    /** Applies a fallible transform, keeping the whole Result (and so whether f failed) secret at
+    label L. f returns Vetted; see with_label. */
+    pub fn try_map<U, E, F>(self, f: F) -> Secret<Result<U, E>, L>
+    where
+        U: InvisibleSideEffectFree + Immutable,
+        E: InvisibleSideEffectFree + Immutable,
+        F: FnOnce(T) -> Vetted<Result<U, E>> + VisibleSideEffectFree,
+    {
+        unsafe { Secret::<Result<U, E>, L>::new(f(self.unwrap()).unwrap()) }
+    }
+
+    /** Reveals only whether self > other, e.g., which millionaire is richer, and nothing else about
+    either value. why names the reason for this one-bit leak, and the "stats" feature counts it per
+    reason (see stats::reveals) as well as a declassification of both labels. */
+    pub fn reveal_gt<L2: lattice::Label>(&self, other: &Secret<T, L2>, why: &'static str) -> bool
+    where
+        T: SafePartialOrd,
+    {
+        #[cfg(feature = "stats")]
+        stats::record_reveal::<L, L2>(why);
+        let _ = why;
+        self.val.safe_gt(&other.val)
+    }
+
+    /** Reveals only whether self == other; see reveal_gt. */
+    pub fn reveal_eq<L2: lattice::Label>(&self, other: &Secret<T, L2>, why: &'static str) -> bool
+    where
+        T: SafePartialEq,
+    {
+        #[cfg(feature = "stats")]
+        stats::record_reveal::<L, L2>(why);
+        let _ = why;
+        self.val.safe_eq(&other.val)
+    }
+
+    /** Compares with another secret, keeping the outcome secret at LOut, which must be at least
+    as secret as both inputs. There's deliberately no PartialOrd or Ord impl, since its plain
+    Ordering would leak the comparison. Incomparable values, e.g., a NaN, give a secret None. */
+    pub fn secret_cmp<L2, LOut>(
+        &self,
+        other: &Secret<T, L2>,
+    ) -> Secret<Option<std::cmp::Ordering>, LOut>
+    where
+        T: SafePartialOrd,
+        L2: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L> + lattice::MoreSecretThan<L2>,
+    {
+        let ord = self.val.safe_partial_cmp(&other.val);
+        unsafe { Secret::<Option<std::cmp::Ordering>, LOut>::new(ord) }
+    }
+
+    /** Like secret_cmp, but for a total order (SafeOrd), so the outcome is never "incomparable". */
+    pub fn secret_total_cmp<L2, LOut>(&self, other: &Secret<T, L2>) -> Secret<std::cmp::Ordering, LOut>
+    where
+        T: SafeOrd,
+        L2: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L> + lattice::MoreSecretThan<L2>,
+    {
+        unsafe { Secret::<std::cmp::Ordering, LOut>::new(self.val.safe_cmp(&other.val)) }
+    }
+
+    /** Bitwise AND of two secrets, e.g. bitboards owned by different principals, without a secret
+    block. The result is labeled LOut, which must be at least as secret as both inputs. */
+    pub fn and<L2, LOut>(self, other: Secret<T, L2>) -> Secret<T, LOut>
+    where
+        T: SafeBitAnd<Output = T>,
+        L2: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L> + lattice::MoreSecretThan<L2>,
+    {
+        unsafe { Secret::<T, LOut>::new(self.unwrap().safe_bitand(other.unwrap())) }
+    }
+
+    /** Bitwise OR of two secrets; see `and`. */
+    pub fn or<L2, LOut>(self, other: Secret<T, L2>) -> Secret<T, LOut>
+    where
+        T: SafeBitOr<Output = T>,
+        L2: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L> + lattice::MoreSecretThan<L2>,
+    {
+        unsafe { Secret::<T, LOut>::new(self.unwrap().safe_bitor(other.unwrap())) }
+    }
+
+    /** XORs self with a secret mask, e.g., to split a secret into one-time-pad shares, or to
+    recombine them. The result is labeled LOut, which must be at least as secret as both inputs. */
+    pub fn xor_mask<L2, LOut>(self, mask: Secret<T, L2>) -> Secret<T, LOut>
+    where
+        T: SafeBitXor<Output = T>,
+        L2: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L> + lattice::MoreSecretThan<L2>,
+    {
+        unsafe { Secret::<T, LOut>::new(self.unwrap().safe_bitxor(mask.unwrap())) }
+    }
+
+    /** Clamps to the secret range [lo, hi] at LOut, which must be at least as secret as all three
+    inputs. Picks the result with MaskSelect rather than branching, like Secret<bool, L>::select.
+    Unlike Ord::clamp, it can't panic on lo > hi without revealing that, so hi wins then. */
+    pub fn clamp<L2, L3, LOut>(self, lo: Secret<T, L2>, hi: Secret<T, L3>) -> Secret<T, LOut>
+    where
+        T: SafePartialOrd + MaskSelect,
+        L2: lattice::Label,
+        L3: lattice::Label,
+        LOut: lattice::Label
+            + lattice::MoreSecretThan<L>
+            + lattice::MoreSecretThan<L2>
+            + lattice::MoreSecretThan<L3>,
+    {
+        let (x, lo, hi) = (self.unwrap(), lo.unwrap(), hi.unwrap());
+        let x = T::masked_select(x.safe_lt(&lo), lo, x);
+        let x = T::masked_select(x.safe_gt(&hi), hi, x);
+        unsafe { Secret::<T, LOut>::new(x) }
+    }
+
+    pub fn clone(&self) -> Secret<T, L>
+    where
+        T: Clone,
+    {
+        Secret::<T, L> {
             val: self.val.clone(),
             _pd: PhantomData,
         }
     }
 
+    pub unsafe fn unwrap_consume_unsafe<M>(self) -> T
+    where
+        M: lattice::MoreSecretThan<L>,
+    {
+        self.unwrap()
+    }
+
+    // Moves the value out. Goes through a raw read since Secret has a Drop impl with the "zeroize" feature.
+    fn unwrap(self) -> T {
+        let this = ManuallyDrop::new(self);
+        unsafe { ManuallyDrop::into_inner(std::ptr::read(&this.val)) }
+    }
+}
+
+/** Types that Secret<bool, L>::select can choose between without branching.
+
+# Safety
+masked_select must not branch on cond: it should only combine a and b with a mask derived from cond. */
+pub unsafe trait MaskSelect: Copy {
+    fn masked_select(cond: bool, a: Self, b: Self) -> Self;
+}
+
+macro_rules! mask_select_impl {
+    ($($t:ty)*) => ($(
+        unsafe impl MaskSelect for $t {
+            #[inline]
+            fn masked_select(cond: bool, a: $t, b: $t) -> $t {
+                // All ones if cond, else all zeros. black_box keeps the optimizer from turning the
+                // mask back into a branch.
+                let mask = std::hint::black_box((cond as $t).wrapping_neg());
+                (a & mask) | (b & !mask)
+            }
+        }
+    )*)
+}
+
+mask_select_impl! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+unsafe impl MaskSelect for bool {
+    #[inline]
+    fn masked_select(cond: bool, a: bool, b: bool) -> bool {
+        let cond = std::hint::black_box(cond);
+        (cond & a) | (!cond & b)
+    }
+}
+
+impl<L: lattice::Label> Secret<bool, L> {
+    /** Returns if_true if self is true, otherwise if_false, without branching on self: the result
+    is computed with a bit mask, so control flow doesn't depend on the secret. This is best effort,
+    since the compiler isn't bound to keep the code branch-free. On x86_64 release builds, the
+    integer impls compile to masking and the bool impl to a cmov, with no conditional jumps. */
+    pub fn select<T>(self, if_true: Secret<T, L>, if_false: Secret<T, L>) -> Secret<T, L>
+    where
+        T: MaskSelect + SecretValueSafe,
+    {
+        let cond = self.unwrap();
+        unsafe { Secret::<T, L>::new(T::masked_select(cond, if_true.unwrap(), if_false.unwrap())) }
+    }
+}
+
+impl<L: lattice::Label> Secret<Vec<u8>, L> {
+    /** Feeds the bytes one at a time through the state machine f, e.g., to parse secret input,
+    keeping the final state at label L. f returns Vetted; see with_label. */
+    pub fn scan_bytes<S, F>(&self, init: S, mut f: F) -> Secret<S, L>
+    where
+        S: SecretValueSafe + InvisibleSideEffectFree,
+        F: FnMut(S, u8) -> Vetted<S> + VisibleSideEffectFree,
+    {
+        let mut state = init;
+        for &byte in self.val.iter() {
+            state = unsafe { f(state, byte).unwrap() };
+        }
+        unsafe { Secret::<S, L>::new(state) }
+    }
+}
+
+impl<L: lattice::Label> Secret<Vec<String>, L> {
+    /** Concatenates the strings with the public separator sep in between, keeping the result at
+    label L. Runs as a secret block, so it only uses allowlisted String operations. */
+    pub fn join_str(&self, sep: &str) -> Secret<String, L> {
+        secret_block!(L {
+            let parts = unwrap_secret_ref(self);
+            let mut joined = std::string::String::from("");
+            for part in parts.iter() {
+                if !std::string::String::is_empty(&joined) {
+                    std::string::String::push_str(&mut joined, sep);
+                }
+                std::string::String::push_str(&mut joined, std::string::String::as_str(part));
+            }
+            wrap_secret(joined)
+        })
+    }
+}
+
+impl<T, L> Secret<Vec<T>, L>
+where
+    L: lattice::Label,
+    T: InvisibleSideEffectFree + Immutable,
+{
+    /** Collects public items into a secret vector at label L, e.g., to seed a buffer from public
+    constants. Unlike transpose_in, the items aren't secrets, and classifying public data can't
+    leak anything. The iterator runs outside of any secret block. */
+    pub fn fill_from_public_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        unsafe { Secret::<Vec<T>, L>::new(iter.into_iter().collect()) }
+    }
+
+    /** Builds a mostly-public buffer with secret holes, e.g., a line of output with public
+    formatting around secret digits: a copy of template whose element at positions[i] is replaced by
+    the i-th secret value. The positions and their count are public, so every position is checked
+    against the template before any secret value is touched, and an out-of-bounds one panics no
+    matter what the values are. */
+    pub fn scatter<const N: usize>(
+        template: &[T],
+        positions: &[usize; N],
+        values: Secret<[T; N], L>,
+    ) -> Self
+    where
+        T: Copy,
+    {
+        for &pos in positions {
+            assert!(
+                pos < template.len(),
+                "scatter position {} out of bounds for a template of length {}",
+                pos,
+                template.len()
+            );
+        }
+        let mut buffer = template.to_vec();
+        for (&pos, value) in positions.iter().zip(values.unwrap()) {
+            buffer[pos] = value;
+        }
+        unsafe { Secret::<Vec<T>, L>::new(buffer) }
+    }
+}
+
+impl<T, L, const N: usize> Secret<[T; N], L>
+where
+    L: lattice::Label,
+    T: InvisibleSideEffectFree + Immutable,
+{
+    /** Builds a secret array from f(0), ..., f(N - 1), instead of filling an uninitialized array
+    in a secret block. f returns Vetted; see with_label. */
+    pub fn new_array<F>(mut f: F) -> Self
+    where
+        F: FnMut(usize) -> Vetted<T> + VisibleSideEffectFree,
+    {
+        let values: [T; N] = std::array::from_fn(|i| unsafe { f(i).unwrap() });
+        unsafe { Secret::<[T; N], L>::new(values) }
+    }
+
+    /** Applies f to each element, keeping label L, e.g., for elementwise kernels over a secret
+    array. The element type can change. f returns Vetted; see with_label. */
+    pub fn map_array<U, F>(self, mut f: F) -> Secret<[U; N], L>
+    where
+        U: InvisibleSideEffectFree + Immutable,
+        F: FnMut(&T) -> Vetted<U> + VisibleSideEffectFree,
+    {
+        let values: [T; N] = self.unwrap();
+        let mapped: [U; N] = std::array::from_fn(|i| unsafe { f(&values[i]).unwrap() });
+        unsafe { Secret::<[U; N], L>::new(mapped) }
+    }
+}
+
+// `.into()` raises secrecy like cast_label. A blanket impl over MoreSecretThan would overlap with
+// core's `impl<T> From<T> for T` (MoreSecretThan is reflexive), so list each strictly-upward edge of
+// the built-in lattice instead. The orphan rule keeps #[derive(Label)] from adding its own.
+macro_rules! raise_label_from {
+    ($($lower:ident => $($higher:ident)*;)*) => ($($(
+        impl<T: SecretValueSafe> From<Secret<T, lattice::$lower>> for Secret<T, lattice::$higher> {
+            fn from(s: Secret<T, lattice::$lower>) -> Self {
+                s.cast_label()
+            }
+        }
+    )*)*)
+}
+
+raise_label_from! {
+    Label_Empty => Label_A Label_B Label_C Label_AB Label_BC Label_AC Label_ABC Label_Top;
+    Label_A => Label_AB Label_AC Label_ABC Label_Top;
+    Label_B => Label_AB Label_BC Label_ABC Label_Top;
+    Label_C => Label_BC Label_AC Label_ABC Label_Top;
+    Label_AB => Label_ABC Label_Top;
+    Label_BC => Label_ABC Label_Top;
+    Label_AC => Label_ABC Label_Top;
+    Label_ABC => Label_Top;
+}
+
+// Methods that only borrow the value, so they also work on unsized secrets such as &Secret<[T], L>.
+impl<T: ?Sized, L: lattice::Label> Secret<T, L>
+where
+    T: SecretValueSafe,
+{
+    // Returning the interior value here, since it's not possible to return a reference to a new Secret (?)
+    pub fn declassify_ref(&self) -> &T {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        // returning an i64 would be another option
+        &self.val
+        //&Secret::<T,lattice::Label_Empty>::new(self.val)
+    }
+
+    pub fn declassify_ref_mut(&mut self) -> &mut T {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        &mut self.val
+    }
+
+    /** Panics, naming both labels, unless this secret is labeled Expected. For checking label
+    inference in tests of label-generic code without declassifying. Debug builds only. */
+    #[cfg(debug_assertions)]
+    pub fn debug_assert_label<Expected: 'static>(&self) {
+        if std::any::TypeId::of::<L>() != std::any::TypeId::of::<Expected>() {
+            panic!(
+                "expected a secret labeled {}, found {}",
+                std::any::type_name::<Expected>(),
+                std::any::type_name::<L>()
+            );
+        }
+    }
+
+    /** Declassifies only what f derives from the value, e.g., a length, without consuming the
+    secret. f gets a borrow for an arbitrary lifetime, so R can't hold on to it, unlike
+    declassify_ref's result. Counted like any other declassification. */
+    pub fn expose_for<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        f(&self.val)
+    }
+
+    /** Borrows self for in-place mutation without writing a secret block. The guard only
+    exposes the value through SecretGuard::map, so it cannot be copied out. */
+    pub fn borrow_secret(&mut self) -> SecretGuard<'_, T, L> {
+        SecretGuard { secret: self, _not_send: PhantomData }
+    }
+
+    /** Selects part of the value (e.g., a field) and returns it as a secret at the same label,
+    the secret-preserving analog of field access. f returns Vetted; see with_label. */
+    pub fn project<'a, U, F>(&'a self, f: F) -> Secret<&'a U, L>
+    where
+        U: InvisibleSideEffectFree + Immutable + ?Sized,
+        F: FnOnce(&'a T) -> Vetted<&'a U> + VisibleSideEffectFree,
+    {
+        unsafe { Secret::<&U, L>::new(f(&self.val).unwrap()) }
+    }
+
+    /** Reads a public part of the value (e.g., a name labeled Label_Empty inside a secret struct)
+    without declassifying the rest. f must project to a nested Secret whose label is public, so
+    only data that was already public comes out. f returns Vetted (see with_label) and should pick
+    the same part regardless of the secret data, since the choice itself isn't hidden. */
+    pub fn inspect_public<'a, U, P, F>(&'a self, f: F) -> &'a U
+    where
+        U: SecretValueSafe + ?Sized + 'static,
+        P: lattice::IsPublic,
+        F: FnOnce(&'a T) -> Vetted<&'a Secret<U, P>> + VisibleSideEffectFree,
+    {
+        unsafe { &f(&self.val).unwrap().val }
+    }
+
     /** (Unsafe) unwrap if label of M allows it.
     Called from secret closures. */
     pub unsafe fn unwrap_unsafe<M>(&self) -> &T
@@ -989,42 +2163,226 @@ where
     {
         &mut self.val
     }
+}
 
-    pub unsafe fn unwrap_consume_unsafe<M>(self) -> T
+impl<T, L: lattice::Label, const N: usize> Secret<[T; N], L>
+where
+    T: InvisibleSideEffectFree + Immutable,
+{
+    /** Borrows element i as its own secret with the same label.
+    The index is public; panics if it is out of bounds. */
+    pub fn at(&self, i: usize) -> Secret<&T, L> {
+        unsafe { Secret::<&T, L>::new(&self.val[i]) }
+    }
+
+    /** Borrows every element as its own secret with the same label. */
+    pub fn each_ref(&self) -> [Secret<&T, L>; N] {
+        std::array::from_fn(|i| self.at(i))
+    }
+}
+
+impl<T, L: lattice::Label> Secret<Box<T>, L>
+where
+    T: InvisibleSideEffectFree + Immutable,
+{
+    /** Borrows the boxed value as a secret with the same label, like Option::as_deref. */
+    pub fn as_deref(&self) -> Secret<&T, L> {
+        unsafe { Secret::<&T, L>::new(&**self.val) }
+    }
+}
+
+impl<T, L: lattice::Label> Secret<Vec<T>, L>
+where
+    T: InvisibleSideEffectFree + Immutable,
+{
+    /** Borrows the elements as a secret slice with the same label. */
+    pub fn as_slice(&self) -> Secret<&[T], L> {
+        unsafe { Secret::<&[T], L>::new(self.val.as_slice()) }
+    }
+
+    /** Gets the element at a secret index, or None if it's out of bounds. Whether the index hit is
+    as secret as the element, so the result is at LOut, which is at least as secret as both labels.
+    Indexing with [] would panic instead, revealing that the index was out of bounds. */
+    pub fn checked_index<LI, LOut>(&self, idx: Secret<usize, LI>) -> Secret<Option<&T>, LOut>
     where
-        M: lattice::MoreSecretThan<L>,
+        LI: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L> + lattice::MoreSecretThan<LI>,
     {
-        self.unwrap()
+        secret_block!(LOut {
+            let v = unwrap_secret_ref(self);
+            let i = unwrap_secret(idx);
+            wrap_secret(<[_]>::get(std::vec::Vec::as_slice(v), i))
+        })
     }
 
-    fn unwrap(self) -> T {
-        ManuallyDrop::into_inner(self.val)
+    /** Appends other's elements after self's, e.g., to merge two parties' secret lists. The result
+    reveals both, so it is at LOut, which is at least as secret as both labels. */
+    pub fn concat<L2, LOut>(self, other: Secret<Vec<T>, L2>) -> Secret<Vec<T>, LOut>
+    where
+        T: VisibleSideEffectFree,
+        L2: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L> + lattice::MoreSecretThan<L2>,
+    {
+        secret_block!(LOut {
+            let mut v = unwrap_secret(self);
+            let mut w = unwrap_secret(other);
+            std::vec::Vec::append(&mut v, &mut w);
+            wrap_secret(v)
+        })
+    }
+
+    /** Splits the elements into those pred matches and those it doesn't, keeping their order. Both
+    halves stay at label L, but their lengths depend on the secret, so declassifying either one (or
+    its length) reveals how many elements matched. pred returns Vetted; see with_label. */
+    pub fn partition<F>(self, mut pred: F) -> (Secret<Vec<T>, L>, Secret<Vec<T>, L>)
+    where
+        F: FnMut(&T) -> Vetted<bool> + VisibleSideEffectFree,
+    {
+        let (matched, unmatched): (Vec<T>, Vec<T>) =
+            self.unwrap().into_iter().partition(|x| unsafe { pred(x).unwrap() });
+        unsafe { (Secret::<Vec<T>, L>::new(matched), Secret::<Vec<T>, L>::new(unmatched)) }
+    }
+}
+
+// Raw byte views of plain-old-data secrets, e.g., for byte-oriented crypto routines.
+#[cfg(feature = "bytemuck")]
+impl<T, L: lattice::Label> Secret<T, L>
+where
+    T: SecretValueSafe + bytemuck::Pod,
+{
+    /** Reinterprets the value as its bytes, keeping the label. Panics if N isn't size_of::<T>(). */
+    pub fn to_secret_bytes<const N: usize>(self) -> Secret<[u8; N], L> {
+        unsafe { Secret::<[u8; N], L>::new(bytemuck::cast(self.unwrap())) }
+    }
+
+    /** Reinterprets secret bytes as a T, keeping the label. Panics if N isn't size_of::<T>(). */
+    pub fn from_secret_bytes<const N: usize>(bytes: Secret<[u8; N], L>) -> Self {
+        unsafe { Secret::<T, L>::new(bytemuck::cast(bytes.unwrap())) }
+    }
+}
+
+// Test-only shortcuts that skip secret_block!. Enabled in this crate's unit tests and, for
+// downstream tests, with the "testutil" feature; never available in normal builds.
+#[cfg(any(test, feature = "testutil"))]
+impl<T, L: lattice::Label> Secret<T, L>
+where
+    T: SecretValueSafe,
+{
+    pub fn from_plaintext_for_test(v: T) -> Self {
+        unsafe { Secret::<T, L>::new(v) }
+    }
+
+    pub fn peek_for_test(&self) -> &T {
+        &self.val
+    }
+}
+
+/** Mutable borrow of a Secret returned by Secret::borrow_secret. Dropping it just ends the borrow.
+The raw pointer marker makes it !Send and !Sync so it cannot be handed to another thread. */
+pub struct SecretGuard<'a, T, L>
+where
+    T: SecretValueSafe + ?Sized,
+    L: lattice::Label,
+{
+    secret: &'a mut Secret<T, L>,
+    _not_send: PhantomData<*const ()>,
+}
+
+unsafe impl<T: SecretValueSafe + ?Sized, L: lattice::Label> InvisibleSideEffectFree for SecretGuard<'_, T, L> {}
+
+impl<T: SecretValueSafe + ?Sized, L: lattice::Label> SecretGuard<'_, T, L> {
+    /** Mutates the secret in place. f returns Vetted (see with_label), and its captures must be
+    VisibleSideEffectFree. */
+    pub fn map<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut T) -> Vetted<()> + VisibleSideEffectFree,
+    {
+        f(&mut self.secret.val);
+        self
     }
 }
 
 // Note: Cannot print value if L: lattice::Label_Empty since it results in conflicting
 // implementations and negative impls aren't supported
-impl<T: SecretValueSafe, L: lattice::Label> fmt::Display for Secret<T, L> {
+impl<T: SecretValueSafe + ?Sized, L: lattice::Label> fmt::Display for Secret<T, L> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "(secret)")
     }
 }
 
-impl<T: SecretValueSafe, L: lattice::Label> fmt::Debug for Secret<T, L> {
+impl<T: SecretValueSafe + ?Sized, L: lattice::Label> fmt::Debug for Secret<T, L> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "(secret)")
     }
 }
 
-impl<T> Secret<T, lattice::Label_Empty>
+/** A secret whose label is only known at run time, so that secrets with different labels can share
+one collection, e.g., a Vec<DynSecret<i32>>. The value can't be reached except through downcast, which
+needs the static label back, so erasing a label never declassifies anything. */
+pub struct DynSecret<T>
+where
+    T: SecretValueSafe + 'static,
+{
+    // Always a Secret<T, L> for the label the value was erased from
+    inner: Box<dyn std::any::Any>,
+    label_name: &'static str,
+    _pd: PhantomData<T>,
+}
+
+impl<T: 'static> !NotSecret for DynSecret<T> {}
+
+impl<T, L> Secret<T, L>
+where
+    T: SecretValueSafe + 'static,
+    L: lattice::Label,
+{
+    /** Forgets the label statically, keeping it as a run-time tag. See DynSecret. */
+    pub fn erase(self) -> DynSecret<T> {
+        DynSecret { inner: Box::new(self), label_name: std::any::type_name::<L>(), _pd: PhantomData }
+    }
+}
+
+impl<T> DynSecret<T>
+where
+    T: SecretValueSafe + 'static,
+{
+    /** Returns the secret if it was erased from a Secret<T, L>, and None for any other label.
+    The label must match exactly; use the Secret's own label conversions after downcasting. */
+    pub fn downcast<L: lattice::Label>(self) -> Option<Secret<T, L>> {
+        self.inner.downcast::<Secret<T, L>>().ok().map(|secret| *secret)
+    }
+
+    /** Whether the secret was erased from a Secret<T, L>. */
+    pub fn is<L: lattice::Label>(&self) -> bool {
+        self.inner.is::<Secret<T, L>>()
+    }
+
+    /** The name of the label the secret was erased from. Labels are public, so this leaks nothing. */
+    pub fn label_name(&self) -> &'static str {
+        self.label_name
+    }
+}
+
+impl<T: SecretValueSafe + 'static> fmt::Debug for DynSecret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(secret: {})", self.label_name)
+    }
+}
+
+// The bounds are on the methods, so that calling them on a secret reports the missing IsPublic
+impl<T, L> Secret<T, L>
 where
     T: SecretValueSafe,
+    L: lattice::Label,
 {
     /**
      * Returns a borrow of the interior value of self.
      * Only valid on public data.
      */
-    pub fn get_value_ref(&self) -> &T {
+    pub fn get_value_ref(&self) -> &T
+    where
+        L: lattice::IsPublic,
+    {
         &self.val
     }
 
@@ -1032,7 +2390,327 @@ where
      * Returns the interior value of self, consuming self in the process.
      * Only valid on public data.
      */
-    pub fn get_value_consume(self) -> T {
-        ManuallyDrop::into_inner(self.val)
+    pub fn get_value_consume(self) -> T
+    where
+        L: lattice::IsPublic,
+    {
+        #[cfg(feature = "stats")]
+        stats::record::<L>();
+        self.unwrap()
+    }
+}
+
+/* `?` on public results. Branching on a secret Result would leak which variant it holds, so Try is
+only implemented at public labels. The residual is a plain Result, so `?` also works in functions
+returning Result. */
+impl<T, E, L: lattice::IsPublic> std::ops::Try for Secret<Result<T, E>, L>
+where
+    T: InvisibleSideEffectFree + Immutable,
+    E: InvisibleSideEffectFree + Immutable,
+{
+    type Output = Secret<T, L>;
+    type Residual = Result<std::convert::Infallible, E>;
+
+    fn from_output(output: Secret<T, L>) -> Self {
+        unsafe { Secret::<Result<T, E>, L>::new(Ok(output.unwrap())) }
+    }
+
+    fn branch(self) -> std::ops::ControlFlow<Self::Residual, Self::Output> {
+        match self.unwrap() {
+            Ok(v) => std::ops::ControlFlow::Continue(unsafe { Secret::<T, L>::new(v) }),
+            Err(e) => std::ops::ControlFlow::Break(Err(e)),
+        }
+    }
+}
+
+impl<T, E, F, L: lattice::IsPublic> std::ops::FromResidual<Result<std::convert::Infallible, E>> for Secret<Result<T, F>, L>
+where
+    T: InvisibleSideEffectFree + Immutable,
+    F: InvisibleSideEffectFree + Immutable + From<E>,
+{
+    fn from_residual(residual: Result<std::convert::Infallible, E>) -> Self {
+        match residual {
+            Err(e) => unsafe { Secret::<Result<T, F>, L>::new(Err(From::from(e))) },
+            Ok(never) => match never {},
+        }
+    }
+}
+
+/** Declassifies a Secret, or every Secret in a tuple or Vec, into plaintext values at once.
+Sugar over calling declassify().get_value_consume() on each one. */
+pub trait DeclassifyAll {
+    type Output;
+    fn declassify_all(self) -> Self::Output;
+}
+
+impl<T: SecretValueSafe, L: lattice::Label> DeclassifyAll for Secret<T, L> {
+    type Output = T;
+    fn declassify_all(self) -> T {
+        self.declassify().get_value_consume()
+    }
+}
+
+impl<A: DeclassifyAll, B: DeclassifyAll> DeclassifyAll for (A, B) {
+    type Output = (A::Output, B::Output);
+    fn declassify_all(self) -> Self::Output {
+        (self.0.declassify_all(), self.1.declassify_all())
+    }
+}
+
+impl<A: DeclassifyAll, B: DeclassifyAll, C: DeclassifyAll> DeclassifyAll for (A, B, C) {
+    type Output = (A::Output, B::Output, C::Output);
+    fn declassify_all(self) -> Self::Output {
+        (self.0.declassify_all(), self.1.declassify_all(), self.2.declassify_all())
+    }
+}
+
+impl<A: DeclassifyAll, B: DeclassifyAll, C: DeclassifyAll, D: DeclassifyAll> DeclassifyAll for (A, B, C, D) {
+    type Output = (A::Output, B::Output, C::Output, D::Output);
+    fn declassify_all(self) -> Self::Output {
+        (self.0.declassify_all(), self.1.declassify_all(), self.2.declassify_all(), self.3.declassify_all())
+    }
+}
+
+impl<T: DeclassifyAll> DeclassifyAll for Vec<T> {
+    type Output = Vec<T::Output>;
+    fn declassify_all(self) -> Self::Output {
+        self.into_iter().map(DeclassifyAll::declassify_all).collect()
+    }
+}
+
+pub fn declassify_all<D: DeclassifyAll>(secrets: D) -> D::Output {
+    secrets.declassify_all()
+}
+
+/** Per-label declassification counters for research and benchmarking, incremented by the
+declassify methods and get_value_consume. Only compiled with the "stats" feature. */
+#[cfg(feature = "stats")]
+pub mod stats {
+    use std::any::TypeId;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::RwLock;
+
+    // The write lock is only taken the first time a label is seen
+    static COUNTS: RwLock<Option<HashMap<TypeId, (&'static str, AtomicU64)>>> = RwLock::new(None);
+
+    pub(crate) fn record<L: 'static>() {
+        let id = TypeId::of::<L>();
+        if let Some((_, count)) = COUNTS.read().unwrap().as_ref().and_then(|counts| counts.get(&id)) {
+            count.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        COUNTS
+            .write()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .entry(id)
+            .or_insert_with(|| (std::any::type_name::<L>(), AtomicU64::new(0)))
+            .1
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    static REVEALS: RwLock<Option<HashMap<&'static str, u64>>> = RwLock::new(None);
+
+    // A one-bit reveal, e.g., Secret::reveal_gt, counts as a declassification of both operands
+    pub(crate) fn record_reveal<L1: 'static, L2: 'static>(why: &'static str) {
+        record::<L1>();
+        record::<L2>();
+        *REVEALS.write().unwrap().get_or_insert_with(HashMap::new).entry(why).or_insert(0) += 1;
+    }
+
+    /** One-bit reveals so far, keyed by the justification passed to, e.g., Secret::reveal_gt. */
+    pub fn reveals() -> HashMap<&'static str, u64> {
+        REVEALS.read().unwrap().clone().unwrap_or_default()
+    }
+
+    /** Declassification counts so far, keyed by label type name, e.g., "secret_structs::lattice::Label_A". */
+    pub fn snapshot() -> HashMap<&'static str, u64> {
+        match COUNTS.read().unwrap().as_ref() {
+            Some(counts) => counts.values().map(|(name, count)| (*name, count.load(Ordering::Relaxed))).collect(),
+            None => HashMap::new(),
+        }
+    }
+}
+
+/** Random secrets, e.g., ship placements or one-time pads. Only compiled with the "rand" feature. */
+#[cfg(feature = "rand")]
+pub mod rand {
+    use super::{lattice, Immutable, InvisibleSideEffectFree, Secret};
+    use ::rand::distributions::{Distribution, Standard};
+    use ::rand::Rng;
+
+    /** Draws a T from rng straight into a Secret<T, L>, so the value is never held as plaintext.
+    The caller supplies the RNG; there is no global one. */
+    pub fn gen_secret<T, L, R>(rng: &mut R) -> Secret<T, L>
+    where
+        T: InvisibleSideEffectFree + Immutable,
+        L: lattice::Label,
+        R: Rng + ?Sized,
+        Standard: Distribution<T>,
+    {
+        unsafe { Secret::<T, L>::new(rng.gen()) }
+    }
+}
+
+/* With the "zeroize" feature, dropping a Secret zeroes its payload if T implements Zeroize.
+The payload itself is still never dropped (see ManuallyDrop in Secret), so the zeroed memory stays
+allocated rather than being handed back to the allocator with secret bytes in it. */
+#[cfg(feature = "zeroize")]
+trait Scrub {
+    fn scrub(&mut self);
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: ?Sized> Scrub for T {
+    default fn scrub(&mut self) {}
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize + ?Sized> Scrub for T {
+    fn scrub(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T, L> Drop for Secret<T, L>
+where
+    T: SecretValueSafe + ?Sized,
+    L: lattice::Label,
+{
+    fn drop(&mut self) {
+        Scrub::scrub(&mut *self.val);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_plaintext_and_peek_for_test() {
+        let s = Secret::<i32, lattice::Label_A>::from_plaintext_for_test(42);
+        assert_eq!(*s.peek_for_test(), 42);
+
+        let pair = Secret::<(String, u8), lattice::Label_AB>::from_plaintext_for_test((String::from("x"), 1));
+        assert_eq!(pair.peek_for_test().0, "x");
+        assert_eq!(pair.peek_for_test().1, 1);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn stats_count_declassifications_per_label() {
+        let a1 = Secret::<i32, lattice::Label_A>::from_plaintext_for_test(1);
+        let a2 = Secret::<i32, lattice::Label_A>::from_plaintext_for_test(2);
+        let b = Secret::<i32, lattice::Label_B>::from_plaintext_for_test(3);
+        let _ = b.declassify_ref();
+        assert_eq!(b.expose_for(|v| *v + 1), 4);
+        let sum = a1.declassify().get_value_consume() + a2.declassify().get_value_consume();
+        assert_eq!(sum, 3);
+
+        let counts = stats::snapshot();
+        assert_eq!(counts[std::any::type_name::<lattice::Label_A>()], 2);
+        assert_eq!(counts[std::any::type_name::<lattice::Label_B>()], 2);
+        assert_eq!(counts[std::any::type_name::<lattice::Label_Empty>()], 2);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn stats_count_reveals_per_justification() {
+        // Labels that the other stats test doesn't use, since the counters are global
+        let c = Secret::<u64, lattice::Label_C>::from_plaintext_for_test(5);
+        let ac = Secret::<u64, lattice::Label_AC>::from_plaintext_for_test(3);
+        assert!(c.reveal_gt(&ac, "unit test: who is richer"));
+        assert!(!c.reveal_eq(&ac, "unit test: same worth"));
+        assert!(!ac.reveal_gt(&c, "unit test: who is richer"));
+
+        let reveals = stats::reveals();
+        assert_eq!(reveals["unit test: who is richer"], 2);
+        assert_eq!(reveals["unit test: same worth"], 1);
+        let counts = stats::snapshot();
+        assert_eq!(counts[std::any::type_name::<lattice::Label_C>()], 3);
+        assert_eq!(counts[std::any::type_name::<lattice::Label_AC>()], 3);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn secret_bytes_round_trip() {
+        let x = Secret::<u32, lattice::Label_A>::from_plaintext_for_test(0x0403_0201);
+        let bytes: Secret<[u8; 4], lattice::Label_A> = x.to_secret_bytes();
+        assert_eq!(*bytes.peek_for_test(), 0x0403_0201u32.to_ne_bytes());
+        let back = Secret::<u32, lattice::Label_A>::from_secret_bytes(bytes);
+        assert_eq!(*back.peek_for_test(), 0x0403_0201);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn gen_secret_draws_from_rng() {
+        let mut rng = ::rand::rngs::mock::StepRng::new(1, 1);
+        let a: Secret<u32, lattice::Label_A> = rand::gen_secret(&mut rng);
+        let b: Secret<u32, lattice::Label_A> = rand::gen_secret(&mut rng);
+        assert_ne!(*a.peek_for_test(), *b.peek_for_test());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize_scrubs_payload_on_drop() {
+        let s = Secret::<Vec<u8>, lattice::Label_A>::from_plaintext_for_test(vec![0xAB; 32]);
+        let buf: *const u8 = s.peek_for_test().as_ptr();
+        drop(s);
+        // The buffer is never freed (the payload is ManuallyDrop), so it is still valid to read
+        for i in 0..32 {
+            assert_eq!(unsafe { std::ptr::read_volatile(buf.add(i)) }, 0);
+        }
+
+        // Payloads without a Zeroize impl are left alone
+        let _ = Secret::<std::path::PathBuf, lattice::Label_A>::from_plaintext_for_test(std::path::PathBuf::from("x"));
+    }
+
+    #[test]
+    fn sort_secret_matches_slice_sort() {
+        // Small LCG so the test is deterministic and needs no extra dependency
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as i32 % 100
+        };
+        for len in 0..=70 {
+            let input: Vec<i32> = (0..len).map(|_| next()).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            let mut s = Secret::<Vec<i32>, lattice::Label_A>::from_plaintext_for_test(input);
+            sort_secret(&mut s);
+            assert_eq!(*s.peek_for_test(), expected, "length {}", len);
+        }
+    }
+
+    // Generic over the output label, so the caller's annotation decides it
+    #[cfg(debug_assertions)]
+    fn join<L1, L2, LOut>(a: Secret<u64, L1>, b: Secret<u64, L2>) -> Secret<u64, LOut>
+    where
+        L1: lattice::Label,
+        L2: lattice::Label,
+        LOut: lattice::Label + lattice::MoreSecretThan<L1> + lattice::MoreSecretThan<L2>,
+    {
+        a.and(b)
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn debug_assert_label_checks_inferred_label() {
+        let a = Secret::<u64, lattice::Label_A>::from_plaintext_for_test(6);
+        let b = Secret::<u64, lattice::Label_B>::from_plaintext_for_test(3);
+        let joined: Secret<u64, lattice::Label_AB> = join(a, b);
+        joined.debug_assert_label::<lattice::Label_AB>();
+
+        let err = std::panic::catch_unwind(|| joined.debug_assert_label::<lattice::Label_ABC>()).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        let expected = format!(
+            "expected a secret labeled {}, found {}",
+            std::any::type_name::<lattice::Label_ABC>(),
+            std::any::type_name::<lattice::Label_AB>()
+        );
+        assert_eq!(*message, expected);
     }
 }